@@ -0,0 +1,279 @@
+//! Derive macro for the [`FromConfig`](https://docs.rs/justconfig/latest/justconfig/from_config/trait.FromConfig.html)
+//! trait of just-config.
+//!
+//! Normally this crate is not used directly. Enable the `derive` feature of
+//! the `justconfig` crate and use the re-exported `JustConfig` macro instead.
+//!
+//! Deriving `JustConfig` for a struct generates an implementation of
+//! `FromConfig` that reads every field from the configuration by driving the
+//! normal configuration pipeline. Every field is read from a sub-path of the
+//! path passed to `from_config` that is named like the field.
+//!
+//! ```rust
+//! use justconfig::{Config, FromConfig};
+//! use justconfig::sources::text::ConfigText;
+//! use justconfig_derive::JustConfig;
+//!
+//! #[derive(JustConfig)]
+//! struct Database {
+//!   host: String,
+//!   #[config(default = 5432)]
+//!   port: u16
+//! }
+//!
+//! #[derive(JustConfig)]
+//! struct ServerConfig {
+//!   #[config(trim, in_range = 1..=64)]
+//!   workers: u32,
+//!   #[config(path = "listen.address", unquote)]
+//!   address: String,
+//!   #[config(explode = ',', trim, values = 1..)]
+//!   tags: Vec<String>,
+//!   timeout: Option<u32>,
+//!   #[config(nested)]
+//!   db: Database
+//! }
+//!
+//! let config_file = r#"
+//! workers=8
+//! tags=web, api
+//! [listen]
+//! address="0.0.0.0"
+//! [db]
+//! host=localhost
+//! "#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new(config_file.as_bytes(), "myconfig.conf").unwrap());
+//!
+//! let server = ServerConfig::from_config(&conf, conf.root()).unwrap();
+//!
+//! assert_eq!(server.workers, 8);
+//! assert_eq!(server.address, "0.0.0.0");
+//! assert_eq!(server.tags, ["web", "api"]);
+//! assert_eq!(server.timeout, None);
+//! assert_eq!(server.db.host, "localhost");
+//! assert_eq!(server.db.port, 5432);
+//! ```
+//!
+//! ## Field types
+//!
+//! The type of the field selects how the values are extracted:
+//!
+//! * `Option<T>` fields are read by calling `try_value`. They are `None` if
+//!   the configuration item has no value.
+//! * `Vec<T>` fields are read by calling `values`. The number of values can be
+//!   limited by using the `values` attribute.
+//! * All other fields are read by calling `value`. The configuration item must
+//!   have exactly one value.
+//!
+//! ## Attributes
+//!
+//! All attributes are placed within `#[config(...)]`.
+//!
+//! | Attribute            | Description                                                                 |
+//! |----------------------|-----------------------------------------------------------------------------|
+//! | `path = "a.b"`       | Read the field from the given path instead of the field name. The components are separated by dots. |
+//! | `trim`, `trim_start`, `trim_end`, `unquote`, `unescape`, `not_empty`, `env` | Call the processor with the same name. |
+//! | `explode = ','`      | Call the `explode` processor with the given delimiter.                      |
+//! | `min = 1`, `max = 10`, `in_range = 1..=10` | Call the validator with the same name.                |
+//! | `values = 1..`       | Number of values a `Vec` field must have. Defaults to `..`.                 |
+//! | `default = expr`     | Value to use if the configuration item has no value. A plain `default` uses `Default::default()`. |
+//! | `nested`             | Read the field by calling `FromConfig::from_config` for the sub-path.      |
+//!
+//! Processors are applied in the order they are listed, validators are
+//! applied after all processors.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, Span};
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, DeriveInput, Data, Fields, Field, Type, Expr, LitStr, LitChar, Ident, Error};
+use syn::spanned::Spanned;
+
+/// Processors that do not take an argument.
+const PROCESSORS: &[&str] = &[ "trim", "trim_start", "trim_end", "unquote", "unescape", "not_empty", "env" ];
+
+/// Validators that take an expression as their argument.
+const VALIDATORS: &[&str] = &[ "min", "max", "in_range" ];
+
+/// Derives the `FromConfig` trait for a struct with named fields.
+///
+/// See the [crate documentation](crate) for the supported attributes.
+#[proc_macro_derive(JustConfig, attributes(config))]
+pub fn derive_just_config(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+
+	expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// How the value of a field is extracted from the configuration.
+enum Kind<'a> {
+	Single,
+	Optional,
+	Multiple(&'a Type)
+}
+
+/// Parsed `#[config(...)]` attributes of a field.
+#[derive(Default)]
+struct FieldConfig {
+	path: Option<LitStr>,
+	processors: Vec<TokenStream2>,
+	validators: Vec<TokenStream2>,
+	values: Option<Expr>,
+	default: Option<TokenStream2>,
+	nested: Option<Span>
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => return Err(Error::new(input.ident.span(), "JustConfig can only be derived for structs with named fields."))
+		},
+		_ => return Err(Error::new(input.ident.span(), "JustConfig can only be derived for structs."))
+	};
+
+	let field_initializers = fields.iter().map(expand_field).collect::<Result<Vec<_>, Error>>()?;
+
+	let name = &input.ident;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::justconfig::FromConfig for #name #type_generics #where_clause {
+			fn from_config(config: &::justconfig::Config, path: ::justconfig::ConfPath) -> ::std::result::Result<Self, ::justconfig::error::ConfigError> {
+				#[allow(unused_imports)]
+				use ::justconfig::item::ValueExtractor as _;
+				#[allow(unused_imports)]
+				use ::justconfig::processors::{Explode as _, Trim as _, Unescape as _, NotEmpty as _, Unquote as _, Subst as _};
+				#[allow(unused_imports)]
+				use ::justconfig::validators::Range as _;
+
+				::std::result::Result::Ok(Self {
+					#(#field_initializers),*
+				})
+			}
+		}
+	})
+}
+
+fn expand_field(field: &Field) -> Result<TokenStream2, Error> {
+	let ident = field.ident.as_ref().unwrap();	// Only named fields are passed to this function.
+	let field_config = parse_field_config(field)?;
+
+	let components = match &field_config.path {
+		Some(path) => {
+			let path_value = path.value();
+			if path_value.split('.').any(str::is_empty) {
+				return Err(Error::new(path.span(), "The path must not contain empty components."));
+			}
+
+			path_value.split('.').map(String::from).collect()
+		},
+		None => vec![ident_name(ident)]
+	};
+	let key = quote! { path.push_all([#(#components),*]) };
+
+	if let Some(nested_span) = field_config.nested {
+		if !field_config.processors.is_empty() || !field_config.validators.is_empty() || field_config.values.is_some() || field_config.default.is_some() {
+			return Err(Error::new(nested_span, "Nested fields do not support processors, validators, values or default."));
+		}
+
+		let ty = &field.ty;
+		return Ok(quote_spanned! { field.span() => #ident: <#ty as ::justconfig::FromConfig>::from_config(config, #key)? });
+	}
+
+	let processors = &field_config.processors;
+	let validators = &field_config.validators;
+	let pipeline = quote! { config.get(#key) #(.#processors)* #(.#validators)* };
+
+	let value = match (field_kind(&field.ty), &field_config.default, &field_config.values) {
+		(Kind::Optional, Some(_), _) => return Err(Error::new(field.span(), "Option fields do not support default.")),
+		(Kind::Single, _, Some(values)) | (Kind::Optional, _, Some(values)) => return Err(Error::new(values.span(), "Only Vec fields support values.")),
+		(Kind::Multiple(_), Some(_), Some(values)) => return Err(Error::new(values.span(), "The attributes values and default can not be combined.")),
+		(Kind::Optional, None, None) => quote! { #pipeline.try_value()? },
+		(Kind::Single, Some(default), None) => quote! { #pipeline.try_value()?.unwrap_or_else(|| #default) },
+		(Kind::Single, None, None) => quote! { #pipeline.value()? },
+		(Kind::Multiple(item_type), Some(default), None) => quote! {
+			{
+				let values: ::std::vec::Vec<#item_type> = #pipeline.values(..)?;
+				if values.is_empty() { #default } else { values }
+			}
+		},
+		(Kind::Multiple(_), None, Some(values)) => quote! { #pipeline.values(#values)? },
+		(Kind::Multiple(_), None, None) => quote! { #pipeline.values(..)? }
+	};
+
+	Ok(quote_spanned! { field.span() => #ident: #value })
+}
+
+fn parse_field_config(field: &Field) -> Result<FieldConfig, Error> {
+	let mut field_config = FieldConfig::default();
+
+	for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("config")) {
+		attr.parse_nested_meta(|meta| {
+			let name = meta.path.get_ident().map(ident_name).unwrap_or_default();
+
+			match name.as_str() {
+				"path" => field_config.path = Some(meta.value()?.parse()?),
+				"explode" => {
+					let delimiter: LitChar = meta.value()?.parse()?;
+					field_config.processors.push(quote! { explode(#delimiter) });
+				},
+				"values" => field_config.values = Some(meta.value()?.parse()?),
+				"default" => {
+					field_config.default = Some(if meta.input.peek(syn::Token![=]) {
+						let default: Expr = meta.value()?.parse()?;
+						quote! { #default }
+					} else {
+						quote! { ::std::default::Default::default() }
+					});
+				},
+				"nested" => field_config.nested = Some(meta.path.span()),
+				processor if PROCESSORS.contains(&processor) => {
+					let method = Ident::new(processor, meta.path.span());
+					field_config.processors.push(quote! { #method() });
+				},
+				validator if VALIDATORS.contains(&validator) => {
+					let method = Ident::new(validator, meta.path.span());
+					let argument: Expr = meta.value()?.parse()?;
+					field_config.validators.push(quote! { #method(#argument) });
+				},
+				_ => return Err(meta.error("Unknown config attribute."))
+			}
+
+			Ok(())
+		})?;
+	}
+
+	Ok(field_config)
+}
+
+/// Returns the name of an identifier without the `r#` prefix of raw identifiers.
+fn ident_name(ident: &Ident) -> String {
+	let name = ident.to_string();
+
+	match name.strip_prefix("r#") {
+		Some(stripped) => String::from(stripped),
+		None => name
+	}
+}
+
+/// Determines the kind of a field by looking at the last segment of its type.
+fn field_kind(ty: &Type) -> Kind<'_> {
+	if let Type::Path(type_path) = ty {
+		if let Some(segment) = type_path.path.segments.last() {
+			if let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments {
+				if let Some(syn::GenericArgument::Type(item_type)) = arguments.args.first() {
+					if segment.ident == "Option" {
+						return Kind::Optional;
+					} else if segment.ident == "Vec" {
+						return Kind::Multiple(item_type);
+					}
+				}
+			}
+		}
+	}
+
+	Kind::Single
+}
@@ -0,0 +1,113 @@
+use justconfig::{Config, FromConfig};
+use justconfig::error::ConfigError;
+use justconfig::sources::text::ConfigText;
+use justconfig_derive::JustConfig;
+
+#[derive(JustConfig, Debug, PartialEq)]
+struct Limits {
+	#[config(min = 1)]
+	connections: u32,
+	#[config(default = 30)]
+	timeout: u32
+}
+
+#[derive(JustConfig, Debug)]
+struct Server {
+	#[config(trim, unquote)]
+	name: String,
+	#[config(path = "listen.port", in_range = 1024..)]
+	port: u16,
+	#[config(explode = ',', trim, values = 1..=3)]
+	tags: Vec<String>,
+	#[config(default = vec![String::from("none")])]
+	plugins: Vec<String>,
+	#[config(default)]
+	debug: bool,
+	description: Option<String>,
+	#[config(max = 10)]
+	r#type: Option<u8>,
+	#[config(nested)]
+	limits: Limits
+}
+
+fn prepare_test_config(config_file: &str) -> Config {
+	let mut c = Config::default();
+	c.add_source(ConfigText::new(config_file.as_bytes(), "myfile").unwrap());
+
+	c
+}
+
+#[test]
+fn derive() {
+	let c = prepare_test_config(r#"
+name="server one"
+tags=a, b , c
+type=5
+[listen]
+port=8080
+[limits]
+connections=10
+"#);
+
+	let server = Server::from_config(&c, c.root()).unwrap();
+
+	assert_eq!(server.name, "server one");
+	assert_eq!(server.port, 8080);
+	assert_eq!(server.tags, ["a", "b", "c"]);
+	assert_eq!(server.plugins, ["none"]);
+	assert!(!server.debug);
+	assert_eq!(server.description, None);
+	assert_eq!(server.r#type, Some(5));
+	assert_eq!(server.limits, Limits { connections: 10, timeout: 30 });
+}
+
+#[test]
+fn sub_path() {
+	let c = prepare_test_config(r#"
+[service.limits]
+connections=5
+timeout=10
+"#);
+
+	let limits = Limits::from_config(&c, c.root().push_all(["service", "limits"])).unwrap();
+	assert_eq!(limits, Limits { connections: 5, timeout: 10 });
+}
+
+#[test]
+fn missing_value() {
+	let c = prepare_test_config("timeout = 10\n");
+
+	match Limits::from_config(&c, c.root()) {
+		Err(ConfigError::ValueNotFound(key)) => assert_eq!(key.to_string(), "connections"),
+		_ => panic!("Missing value not detected.")
+	}
+}
+
+#[test]
+fn validation() {
+	let c = prepare_test_config(r#"
+name="server"
+tags=a
+[listen]
+port=80
+[limits]
+connections=10
+"#);
+
+	let error = Server::from_config(&c, c.root()).unwrap_err();
+	assert_eq!(error.to_string(), "must be >= 1024.@'conf:myfile:5'");
+}
+
+#[test]
+fn value_count() {
+	let c = prepare_test_config(r#"
+name="server"
+tags=a, b, c, d
+[listen]
+port=8080
+[limits]
+connections=10
+"#);
+
+	assert!(matches!(Server::from_config(&c, c.root()), Err(ConfigError::TooManyValues(3, _, _))));
+}
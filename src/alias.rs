@@ -0,0 +1,156 @@
+//! Aliases for renamed configuration keys.
+//!
+//! If a configuration key is renamed, existing configuration files still use
+//! the old name. By calling [`add_alias`](crate::Config::add_alias) the old
+//! name is registered as an alias of the new name. Requesting the new key by
+//! calling [`get`](crate::Config::get) returns the value of the old key if the
+//! new key is not set. The returned configuration item carries the new key.
+//!
+//! Every time the value of an old key is used, a [`Deprecation`] warning is
+//! recorded. It contains the source locations of the values that used the old
+//! name. The application can retrieve the warnings by calling
+//! [`deprecations`](crate::Config::deprecations) and log them to ask the user
+//! to update the configuration.
+//!
+//! If the old and the new key or two old keys are both set, it is not clear
+//! which value the user intended to use. In this case `get` returns a
+//! [`ConfigError::AliasConflict`](crate::error::ConfigError::AliasConflict)
+//! error.
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::ConfPath;
+//! # use justconfig::item::ValueExtractor;
+//! # use justconfig::sources::text::ConfigText;
+//! #
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new("db.host=localhost".as_bytes(), "myconfig.conf").unwrap());
+//! conf.add_alias(conf.root().push_all(["db", "host"]), conf.root().push_all(["database", "host"]));
+//!
+//! let host: String = conf.get(conf.root().push_all(["database", "host"])).value().unwrap();
+//! assert_eq!(host, "localhost");
+//!
+//! let deprecations = conf.deprecations();
+//! assert_eq!(deprecations.len(), 1);
+//! assert_eq!(deprecations[0].to_string(), "Config key 'db.host' is deprecated, use 'database.host' instead@['conf:myconfig.conf:1']");
+//! ```
+//!
+//! Old keys are marked as used if the new key is requested. Therefore they are
+//! not reported by [`unused_keys`](crate::Config::unused_keys).
+use crate::confpath::ConfPath;
+use crate::item::SourceLocation;
+use std::sync::Arc;
+use std::fmt;
+
+/// Warning recorded if the value of a deprecated alias was used.
+#[derive(Debug, Clone)]
+pub struct Deprecation {
+	old_key: ConfPath,
+	new_key: ConfPath,
+	locations: Vec<Arc<dyn SourceLocation>>
+}
+
+impl Deprecation {
+	pub(crate) fn new(old_key: ConfPath, new_key: ConfPath, locations: Vec<Arc<dyn SourceLocation>>) -> Self {
+		Self {
+			old_key,
+			new_key,
+			locations
+		}
+	}
+
+	/// Returns the deprecated configuration path that was used.
+	pub fn old_key(&self) -> &ConfPath {
+		&self.old_key
+	}
+
+	/// Returns the configuration path that replaces the deprecated one.
+	pub fn new_key(&self) -> &ConfPath {
+		&self.new_key
+	}
+
+	/// Returns the source locations of all values set for the deprecated key.
+	pub fn locations(&self) -> &[Arc<dyn SourceLocation>] {
+		&self.locations
+	}
+}
+
+impl fmt::Display for Deprecation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Config key '{}' is deprecated, use '{}' instead@[", self.old_key, self.new_key)?;
+		for (i, location) in self.locations.iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+
+			write!(f, "'{}'", location)?;
+		}
+		write!(f, "]")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Config;
+	use crate::ConfPath;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use crate::sources::text::ConfigText;
+
+	fn prepare_test_config(config_file: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigText::new(config_file.as_bytes(), "myconfig.conf").unwrap());
+		c.add_alias(ConfPath::from(&["db", "host"]), ConfPath::from(&["database", "host"]));
+		c.add_alias(ConfPath::from(&["dbhost"]), ConfPath::from(&["database", "host"]));
+
+		c
+	}
+
+	#[test]
+	fn new_key() {
+		let c = prepare_test_config("database.host=new\n");
+
+		assert_eq!((c.get(ConfPath::from(&["database", "host"])).value() as Result<String, ConfigError>).unwrap(), "new");
+		assert!(c.deprecations().is_empty());
+	}
+
+	#[test]
+	fn old_key() {
+		let c = prepare_test_config("\ndbhost=old\n");
+
+		let item = c.get(ConfPath::from(&["database", "host"])).unwrap();
+		assert_eq!(item.key(), &ConfPath::from(&["database", "host"]));
+		assert_eq!((c.get(ConfPath::from(&["database", "host"])).value() as Result<String, ConfigError>).unwrap(), "old");
+
+		// The warning is only recorded once.
+		let deprecations = c.deprecations();
+		assert_eq!(deprecations.len(), 1);
+		assert_eq!(deprecations[0].old_key(), &ConfPath::from(&["dbhost"]));
+		assert_eq!(deprecations[0].new_key(), &ConfPath::from(&["database", "host"]));
+		assert_eq!(deprecations[0].locations()[0].to_string(), "conf:myconfig.conf:2");
+
+		assert!(c.unused_keys().is_empty());
+	}
+
+	#[test]
+	fn missing() {
+		let c = prepare_test_config("other=1\n");
+
+		assert!(matches!(c.get(ConfPath::from(&["database", "host"])), Err(ConfigError::ValueNotFound(_))));
+		assert!(c.deprecations().is_empty());
+	}
+
+	#[test]
+	fn conflict() {
+		let c = prepare_test_config("database.host=new\ndb.host=old\n");
+
+		let error = c.get(ConfPath::from(&["database", "host"])).err().unwrap();
+		assert_eq!(error.to_string(), "Config key 'database.host' and its deprecated alias 'db.host' are both set@['conf:myconfig.conf:1', 'conf:myconfig.conf:2']");
+		assert_eq!(error.location().unwrap().to_string(), "conf:myconfig.conf:1");
+
+		let c = prepare_test_config("dbhost=old\ndb.host=old\n");
+		let error = c.get(ConfPath::from(&["database", "host"])).err().unwrap();
+		assert!(matches!(error, ConfigError::AliasConflict(_, _, _, _)));
+		assert_eq!(error.to_string(), "Deprecated aliases 'db.host' and 'dbhost' of config key 'database.host' are both set@['conf:myconfig.conf:2', 'conf:myconfig.conf:1']");
+	}
+}
@@ -1,7 +1,6 @@
 use std::iter::{Iterator, FromIterator};
-use std::rc::{Rc, Weak};
+use std::sync::{Arc, Weak, RwLock, PoisonError};
 use std::fmt::{self, Display};
-use std::cell::RefCell;
 use std::collections::{HashMap, hash_map::Entry};
 use std::hash::{Hash, Hasher};
 use std::ops::Add;
@@ -10,7 +9,7 @@ use std::ops::Add;
 struct ConfPathData {
 	name: Option<String>,
 	parent: Weak<ConfPathData>,
-	children: RefCell<HashMap<String, Rc<ConfPathData>>>
+	children: RwLock<HashMap<String, Arc<ConfPathData>>>
 }
 
 /// An owned, immutable configuration path.
@@ -58,19 +57,21 @@ struct ConfPathData {
 /// configuration trees compare equal. It uses a shortcut if the compared values
 /// originate from the same configuration tree.
 ///
+/// `ConfPath` is `Send` and `Sync`. Config paths of the same configuration
+/// tree can be used from multiple threads at the same time.
 ///
 #[derive(Debug, Clone)]
 pub struct ConfPath {
-	data: Rc<ConfPathData>,
-	root: Rc<ConfPathData>
+	data: Arc<ConfPathData>,
+	root: Arc<ConfPathData>
 }
 
 impl Default for ConfPath {
 	fn default() -> Self {
-		let root_node = Rc::new(ConfPathData {
+		let root_node = Arc::new(ConfPathData {
 			name: None,
 			parent: Weak::new(),
-			children: RefCell::new(HashMap::default())
+			children: RwLock::new(HashMap::default())
 		});
 
 		// The root node holds two references to itself.
@@ -94,13 +95,13 @@ impl PartialEq for ConfPath {
 		// If the two elements point to the same data
 		// they share the same config root and will definitely be equal.
 		// This is ensured by the way `push` is implemented.
-		if Rc::ptr_eq(&self.data, &other.data) {
+		if Arc::ptr_eq(&self.data, &other.data) {
 			true
 		} else {
 			// If the root of the two ConfPath instances is the same
 			// and the data pointers differ they are different. We do not
 			// need to do more comparison.
-			if Rc::ptr_eq(&self.root, &other.root) {
+			if Arc::ptr_eq(&self.root, &other.root) {
 				false
 			} else {
 				// If the ConfPath instances do not share the same data and root
@@ -150,20 +151,13 @@ impl IntoIterator for ConfPath {
 
 impl Display for ConfPath {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		let mut pos = self.clone();
-
-		let mut add_delimiter = false;
-
-		while !pos.is_root() {
-			if add_delimiter {
+		for (i, component) in self.iter().enumerate() {
+			if i > 0 {
 				write!(f, ".")?;
 			}
 
-			// We already checked that this is not the root node. So unwrapping pop() and tail_component_name() is ok here.
-			write!(f, "{}", pos.tail_component_name().unwrap())?;
-			pos = pos.pop().unwrap().1;
-
-			add_delimiter = true;
+			// The iterator never returns the root node. So unwrapping tail_component_name() is ok here.
+			write!(f, "{}", component.tail_component_name().unwrap())?;
 		}
 
 		Ok(())
@@ -177,7 +171,7 @@ impl <'a, T: AsRef<[&'a str]>> From<T> for ConfPath {
 }
 
 impl ConfPath {
-	fn new(root: &Rc<ConfPathData>, data: Rc<ConfPathData>) -> Self {
+	fn new(root: &Arc<ConfPathData>, data: Arc<ConfPathData>) -> Self {
 		Self {
 			data,
 			root: root.clone()	// Increment the reference count on the root node
@@ -198,12 +192,19 @@ impl ConfPath {
 	/// assert_eq!(cp_ab, ConfPath::from(&["a", "b"]));
 	/// ```
 	pub fn push(&self, component: &str) -> Self {
-		match self.data.children.borrow_mut().entry(component.to_owned()) {
+		// Most of the time the child already exists. Try to find it without
+		// blocking other threads first.
+		if let Some(child) = self.data.children.read().unwrap_or_else(PoisonError::into_inner).get(component) {
+			return Self::new(&self.root, child.clone());
+		}
+
+		// The child map is never left in an inconsistent state. Therefore a poisoned lock can be ignored.
+		match self.data.children.write().unwrap_or_else(PoisonError::into_inner).entry(component.to_owned()) {
 			Entry::Occupied(child) => Self::new(&self.root, child.get().clone()),
-			Entry::Vacant(child) => Self::new(&self.root, child.insert(Rc::new(ConfPathData {
+			Entry::Vacant(child) => Self::new(&self.root, child.insert(Arc::new(ConfPathData {
 				name: Some(component.to_owned()),
-				parent: Rc::downgrade(&self.data),
-				children: RefCell::new(HashMap::default())
+				parent: Arc::downgrade(&self.data),
+				children: RwLock::new(HashMap::default())
 			})).clone())
 		}
 	}
@@ -272,7 +273,83 @@ impl ConfPath {
 	/// ```
 	pub fn is_root(&self) -> bool {
 		// On the root node the data and the root pointer point to the same spot
-		Rc::ptr_eq(&self.data, &self.root)
+		Arc::ptr_eq(&self.data, &self.root)
+	}
+
+	/// Checks if this config path starts with the passed prefix.
+	///
+	/// Every config path starts with the root node and with itself. The
+	/// config paths do not have to originate from the same configuration tree.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use justconfig::ConfPath;
+	///
+	/// let cp = ConfPath::default().push_all(["a", "b"]);
+	///
+	/// assert!(cp.starts_with(&ConfPath::default()));
+	/// assert!(cp.starts_with(&ConfPath::from(&["a"])));
+	/// assert!(cp.starts_with(&ConfPath::from(&["a", "b"])));
+	/// assert!(!cp.starts_with(&ConfPath::from(&["b"])));
+	/// ```
+	pub fn starts_with(&self, prefix: &ConfPath) -> bool {
+		let mut pos = self.clone();
+
+		loop {
+			if pos == *prefix {
+				break true;
+			}
+
+			match pos.pop() {
+				Some((_, parent)) => pos = parent,
+				None => break false
+			}
+		}
+	}
+
+	/// Removes the passed prefix from this config path.
+	///
+	/// Returns a new config path containing the components following the
+	/// prefix or `None` if this config path does not start with `prefix`. The
+	/// returned path belongs to the same configuration tree as this path.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use justconfig::ConfPath;
+	///
+	/// let cp = ConfPath::default().push_all(["a", "b", "c"]);
+	///
+	/// assert_eq!(cp.strip_prefix(&ConfPath::from(&["a"])).unwrap(), ConfPath::from(&["b", "c"]));
+	/// assert!(cp.strip_prefix(&ConfPath::from(&["b"])).is_none());
+	/// ```
+	pub fn strip_prefix(&self, prefix: &ConfPath) -> Option<Self> {
+		if !self.starts_with(prefix) {
+			return None;
+		}
+
+		let prefix_len = prefix.iter().count();
+		Some(Self::new(&self.root, self.root.clone()).push_all(self.component_names().into_iter().skip(prefix_len)))
+	}
+
+	/// Appends all components of the passed config path to this config path
+	/// and returns the new path.
+	///
+	/// The passed path may originate from a different configuration tree. The
+	/// returned path belongs to the configuration tree of this path.
+	///
+	/// # Example
+	///
+	/// ```
+	/// use justconfig::ConfPath;
+	///
+	/// let cp = ConfPath::default().push("a");
+	///
+	/// assert_eq!(cp.join(&ConfPath::from(&["b", "c"])), ConfPath::from(&["a", "b", "c"]));
+	/// ```
+	pub fn join(&self, other: &ConfPath) -> Self {
+		self.push_all(other.component_names())
 	}
 
 	/// Returns the name of the last component of this config path.
@@ -293,6 +370,14 @@ impl ConfPath {
 		self.data.name.as_deref()
 	}
 
+	/// Returns the names of all components of the path.
+	///
+	/// The names are returned first to last. Comparing the returned vectors
+	/// sorts config paths by their components.
+	pub(crate) fn component_names(&self) -> Vec<String> {
+		self.iter().map(|c| String::from(c.tail_component_name().unwrap())).collect()	// The iterator never returns the root node. So unwrapping is ok here.
+	}
+
 	/// Returns an iterator that enumerates the components of the path.
 	///
 	/// The iterator returns the components first to last.
@@ -319,7 +404,7 @@ impl ConfPath {
 	/// }
 	/// ```
 	pub fn children(&self) -> impl Iterator<Item=ConfPath> {
-		Vec::from_iter(self.data.children.borrow().values().map(|v| ConfPath::new(&self.root, v.clone()))).into_iter()
+		Vec::from_iter(self.data.children.read().unwrap_or_else(PoisonError::into_inner).values().map(|v| ConfPath::new(&self.root, v.clone()))).into_iter()
 	}
 }
 
@@ -394,6 +479,47 @@ mod tests {
 		assert!(!cp_node.is_root());
 	}
 
+	#[test]
+	fn starts_with() {
+		let cp = ConfPath::default().push_all(["a", "b", "c"]);
+
+		assert!(cp.starts_with(&ConfPath::default()));
+		assert!(cp.starts_with(&cp));
+		assert!(cp.starts_with(&cp.pop().unwrap().1));
+		assert!(cp.starts_with(&ConfPath::from(&["a", "b"])));
+
+		assert!(!cp.starts_with(&ConfPath::from(&["a", "c"])));
+		assert!(!cp.starts_with(&ConfPath::from(&["a", "b", "c", "d"])));
+		assert!(!ConfPath::default().starts_with(&cp));
+	}
+
+	#[test]
+	fn strip_prefix_and_join() {
+		let cp = ConfPath::default().push_all(["a", "b", "c"]);
+
+		let stripped = cp.strip_prefix(&ConfPath::from(&["a", "b"])).unwrap();
+		check_path(&stripped, &["c"]);
+		assert_eq!(stripped.iter().count(), 1);
+		assert!(cp.strip_prefix(&cp).unwrap().is_root());
+		assert_eq!(cp.strip_prefix(&ConfPath::default()).unwrap(), cp);
+		assert!(cp.strip_prefix(&ConfPath::from(&["a", "c"])).is_none());
+
+		// The stripped path belongs to the tree of the original path.
+		assert!(Arc::ptr_eq(&stripped.root, &cp.root));
+
+		let joined = ConfPath::from(&["x"]).join(&stripped);
+		check_path(&joined, &["x", "c"]);
+		assert_eq!(ConfPath::default().join(&cp), cp);
+		assert_eq!(cp.join(&ConfPath::default()), cp);
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(ConfPath::default().to_string(), "");
+		assert_eq!(ConfPath::from(&["a"]).to_string(), "a");
+		assert_eq!(ConfPath::from(&["a", "b", "c"]).to_string(), "a.b.c");
+	}
+
 	#[test]
 	fn add() {
 		let cp = ConfPath::default();
@@ -470,8 +596,8 @@ mod tests {
 				let inode = root.push("internal");
 				lnode = inode.push("leaf");
 
-				wr_root = Rc::downgrade(&root.data);
-				wr_inode = Rc::downgrade(&inode.data);
+				wr_root = Arc::downgrade(&root.data);
+				wr_inode = Arc::downgrade(&inode.data);
 
 				assert!(wr_root.upgrade().is_some());
 				assert!(wr_inode.upgrade().is_some());
@@ -533,4 +659,23 @@ mod tests {
 		root_child_iter.for_each(|c| assert!(reference_set.remove(&c), "Iterator returned to many elements."));
 		assert_eq!(reference_set.len(), 0, "Iterator returned not enough elements.");
 	}
+
+	#[test]
+	fn threads() {
+		let cp = ConfPath::default();
+
+		// Concurrently create the same nodes in different threads.
+		let handles: Vec<_> = (0..4).map(|_| {
+			let thread_cp = cp.clone();
+			std::thread::spawn(move || (0..100).map(|i| thread_cp.push_all(["a", &i.to_string()])).collect::<Vec<_>>())
+		}).collect();
+
+		let results: Vec<Vec<ConfPath>> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+
+		// Every thread must have got the same nodes.
+		for result in results.iter().skip(1) {
+			assert!(result.iter().zip(results[0].iter()).all(|(a, b)| Arc::ptr_eq(&a.data, &b.data)));
+		}
+		assert_eq!(cp.push("a").children().count(), 100);
+	}
 }
@@ -0,0 +1,671 @@
+//! Deserializing configuration information with serde.
+//!
+//! This module is only available if the `serde` feature is enabled.
+//!
+//! Applications that already use serde structs for their configuration can
+//! fill them directly from a [`Config`]. The [`Deserializer`] maps the serde
+//! data model onto the configuration tree:
+//!
+//! * Structs and maps are read from the children of the current configuration
+//!   path. Every field or map key is a sub-path.
+//! * Sequences are read from the values of a multi-valued configuration item.
+//!   If the configuration item has no values, the children named `0`, `1`, ...
+//!   are used instead. This way sequences of structs can be represented.
+//! * Scalars are converted by using their `FromStr` implementation. This is the
+//!   same conversion [`ValueExtractor`](crate::item::ValueExtractor) uses.
+//! * Enums are read from the value of a configuration item. Variants with data
+//!   are read from a sub-path named like the variant.
+//!
+//! Unknown children of a struct are passed to serde. Therefore
+//! `#[serde(deny_unknown_fields)]` rejects configuration keys that do not
+//! belong to a field. Without this attribute unknown keys are ignored and not
+//! recorded as used.
+//!
+//! Conversion errors are reported as [`ConfigError::ValueError`] and carry the
+//! [`SourceLocation`](crate::item::SourceLocation) of the invalid value. Every
+//! key read by the deserializer is recorded as used (see
+//! [`unused_keys`](Config::unused_keys)).
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::sources::text::ConfigText;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Database {
+//!   host: String,
+//!   #[serde(default)]
+//!   port: Option<u16>
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct ServerConfig {
+//!   workers: u32,
+//!   tags: Vec<String>,
+//!   db: Database
+//! }
+//!
+//! let config_file = "workers=8\ntags=web\ntags=api\n[db]\nhost=localhost\n";
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new(config_file.as_bytes(), "myconfig.conf").unwrap());
+//!
+//! let server: ServerConfig = justconfig::de::from_config(&conf).unwrap();
+//!
+//! assert_eq!(server.workers, 8);
+//! assert_eq!(server.tags, ["web", "api"]);
+//! assert_eq!(server.db.host, "localhost");
+//! assert_eq!(server.db.port, None);
+//! ```
+use crate::Config;
+use crate::confpath::ConfPath;
+use crate::error::ConfigError;
+use crate::item::{Value, ValueExtractor};
+use std::str::FromStr;
+use std::error::Error;
+use std::sync::Arc;
+use std::fmt;
+use serde::de::{self, Visitor, IntoDeserializer, DeserializeSeed};
+
+/// Deserializes a value from the root of the configuration.
+pub fn from_config<'de, T: de::Deserialize<'de>>(config: &Config) -> Result<T, ConfigError> {
+	T::deserialize(Deserializer::new(config, config.root()))
+}
+
+/// Deserializes a value from the passed configuration path.
+pub fn from_config_path<'de, T: de::Deserialize<'de>>(config: &Config, path: ConfPath) -> Result<T, ConfigError> {
+	T::deserialize(Deserializer::new(config, path))
+}
+
+impl de::Error for ConfigError {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		ConfigError::Deserialize(msg.to_string(), None)
+	}
+}
+
+/// Adds the configuration path to errors created by serde.
+fn with_path(error: ConfigError, path: &ConfPath) -> ConfigError {
+	match error {
+		ConfigError::Deserialize(msg, None) => ConfigError::Deserialize(msg, Some(path.clone())),
+		error => error
+	}
+}
+
+/// Adds the source location of the value to errors created by serde.
+fn with_location(error: ConfigError, value: &Value<String>) -> ConfigError {
+	match error {
+		ConfigError::Deserialize(msg, None) => ConfigError::ValueError(msg.into(), value.source()),
+		error => error
+	}
+}
+
+/// Converts a single configuration value by using its `FromStr` implementation.
+fn parse<T: FromStr>(value: &Value<String>) -> Result<T, ConfigError> where T::Err: Error + Send + Sync + 'static {
+	value.value().parse().map_err(|e| ConfigError::from_error(e, value.source()))
+}
+
+/// Deserializer reading from a configuration path.
+///
+/// See the [module documentation](self) for details.
+pub struct Deserializer<'c> {
+	config: &'c Config,
+	path: ConfPath,
+	/// All configuration keys below `path`. They are enumerated once and
+	/// split between the children to not query the sources for every field.
+	keys: Vec<ConfPath>
+}
+
+impl <'c> Deserializer<'c> {
+	/// Creates a deserializer that reads from the passed configuration path.
+	pub fn new(config: &'c Config, path: ConfPath) -> Self {
+		let keys = config.keys_under(&path);
+
+		Self {
+			config,
+			path,
+			keys
+		}
+	}
+
+	/// Returns the values of the current path. A missing configuration item
+	/// is treated like an item without values.
+	fn values(&self) -> Result<Vec<Arc<Value<String>>>, ConfigError> {
+		match self.config.get(self.path.clone()) {
+			Ok(item) => Ok(item.values().to_vec()),
+			Err(ConfigError::ValueNotFound(_)) => Ok(Vec::default()),
+			Err(error) => Err(error)
+		}
+	}
+
+	fn value<T: FromStr>(&self) -> Result<T, ConfigError> where T::Err: Error + Send + Sync + 'static {
+		self.config.get(self.path.clone()).value()
+	}
+
+	/// Returns the direct children of the current path that are known to the
+	/// configuration sources together with the keys below every child.
+	fn children(&self) -> Vec<(String, Vec<ConfPath>)> {
+		let depth = self.path.iter().count();
+
+		let mut children: Vec<(String, Vec<ConfPath>)> = Vec::new();
+		for key in self.keys.iter() {
+			if let Some(name) = key.iter().nth(depth).and_then(|child| child.tail_component_name().map(String::from)) {
+				// The keys are sorted. So all keys of a child are consecutive.
+				match children.last_mut() {
+					Some((last, keys)) if *last == name => keys.push(key.clone()),
+					_ => children.push((name, vec![key.clone()]))
+				}
+			}
+		}
+
+		children
+	}
+
+	fn child(&self, name: &str, keys: Vec<ConfPath>) -> Self {
+		Self {
+			config: self.config,
+			path: self.path.push(name),
+			keys
+		}
+	}
+
+	fn exists(&self) -> Result<bool, ConfigError> {
+		Ok(!self.values()?.is_empty() || self.keys.iter().any(|key| *key != self.path))
+	}
+}
+
+macro_rules! deserialize_scalar {
+	($($method:ident => $visit:ident,)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+				visitor.$visit(self.value()?).map_err(|e| with_path(e, &self.path))
+			}
+		)*
+	}
+}
+
+impl <'de, 'c> de::Deserializer<'de> for Deserializer<'c> {
+	type Error = ConfigError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let mut values = self.values()?;
+
+		match values.len() {
+			0 if !self.exists()? => visitor.visit_unit(),
+			0 => self.deserialize_map(visitor),
+			1 => ValueDeserializer(values.remove(0)).deserialize_any(visitor),
+			_ => self.deserialize_seq(visitor)
+		}
+	}
+
+	deserialize_scalar! {
+		deserialize_bool => visit_bool,
+		deserialize_i8 => visit_i8,
+		deserialize_i16 => visit_i16,
+		deserialize_i32 => visit_i32,
+		deserialize_i64 => visit_i64,
+		deserialize_i128 => visit_i128,
+		deserialize_u8 => visit_u8,
+		deserialize_u16 => visit_u16,
+		deserialize_u32 => visit_u32,
+		deserialize_u64 => visit_u64,
+		deserialize_u128 => visit_u128,
+		deserialize_f32 => visit_f32,
+		deserialize_f64 => visit_f64,
+		deserialize_char => visit_char,
+		deserialize_string => visit_string,
+	}
+
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_string(visitor)
+	}
+
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_byte_buf(self.value::<String>()?.into_bytes()).map_err(|e| with_path(e, &self.path))
+	}
+
+	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_bytes(visitor)
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		if self.exists()? {
+			visitor.visit_some(self)
+		} else {
+			visitor.visit_none()
+		}
+	}
+
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_unit(visitor)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let values = self.values()?;
+
+		let result = if !values.is_empty() {
+			visitor.visit_seq(ValueSeqAccess(values.into_iter()))
+		} else {
+			// Use the children with numeric names as the elements of the sequence.
+			let mut indices: Vec<(usize, String, Vec<ConfPath>)> = self.children().into_iter().filter_map(|(name, keys)| name.parse().ok().map(|index| (index, name, keys))).collect();
+			indices.sort_by_key(|(index, _, _)| *index);
+
+			visitor.visit_seq(PathSeqAccess {
+				children: indices.into_iter().map(|(_, name, keys)| self.child(&name, keys)).collect::<Vec<_>>().into_iter()
+			})
+		};
+
+		result.map_err(|e| with_path(e, &self.path))
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let children = self.children();
+
+		visitor.visit_map(PathMapAccess {
+			parent: &self,
+			children: children.into_iter(),
+			next: None
+		}).map_err(|e| with_path(e, &self.path))
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		// Only pass fields that are set. This allows serde to apply the
+		// defaults for missing fields.
+		let mut known_children = self.children();
+		let mut children = Vec::with_capacity(fields.len());
+		for field in fields {
+			let keys = match known_children.iter().position(|(name, _)| name == field) {
+				Some(index) => known_children.swap_remove(index).1,
+				None => Vec::default()
+			};
+
+			if self.child(field, keys.clone()).exists()? {
+				children.push((String::from(*field), keys));
+			}
+		}
+
+		// Unknown children are passed, too. This way `deny_unknown_fields` can
+		// reject them. Otherwise serde ignores them without reading their values.
+		children.extend(known_children);
+
+		visitor.visit_map(PathMapAccess {
+			parent: &self,
+			children: children.into_iter(),
+			next: None
+		}).map_err(|e| with_path(e, &self.path))
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		let mut values = self.values()?;
+
+		if values.is_empty() {
+			// Variants with data are stored within a sub-path named like the variant.
+			let mut children = self.children();
+			if children.len() != 1 {
+				return Err(ConfigError::Deserialize(String::from("exactly one enum variant must be set"), Some(self.path)));
+			}
+
+			let (variant, keys) = children.remove(0);
+			visitor.visit_enum(PathEnumAccess {
+				content: self.child(&variant, keys),
+				variant
+			}).map_err(|e| with_path(e, &self.path))
+		} else {
+			if values.len() > 1 {
+				return Err(ConfigError::TooManyValues(1, self.path, values[1..].iter().map(|v| v.source()).collect()));
+			}
+
+			ValueDeserializer(values.remove(0)).deserialize_enum(name, variants, visitor)
+		}
+	}
+
+	fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_string(visitor)
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_unit()
+	}
+}
+
+/// Deserializer for a single configuration value.
+struct ValueDeserializer(Arc<Value<String>>);
+
+macro_rules! deserialize_value {
+	($($method:ident => $visit:ident,)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+				visitor.$visit(parse(&self.0)?).map_err(|e| with_location(e, &self.0))
+			}
+		)*
+	}
+}
+
+impl <'de> de::Deserializer<'de> for ValueDeserializer {
+	type Error = ConfigError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_str(self.0.value()).map_err(|e| with_location(e, &self.0))
+	}
+
+	deserialize_value! {
+		deserialize_bool => visit_bool,
+		deserialize_i8 => visit_i8,
+		deserialize_i16 => visit_i16,
+		deserialize_i32 => visit_i32,
+		deserialize_i64 => visit_i64,
+		deserialize_i128 => visit_i128,
+		deserialize_u8 => visit_u8,
+		deserialize_u16 => visit_u16,
+		deserialize_u32 => visit_u32,
+		deserialize_u64 => visit_u64,
+		deserialize_u128 => visit_u128,
+		deserialize_f32 => visit_f32,
+		deserialize_f64 => visit_f64,
+		deserialize_char => visit_char,
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		let variant: de::value::StrDeserializer<ConfigError> = self.0.value().as_str().into_deserializer();
+
+		visitor.visit_enum(variant).map_err(|e| with_location(e, &self.0))
+	}
+
+	serde::forward_to_deserialize_any! {
+		str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+	}
+}
+
+/// Sequence of the values of a configuration item.
+struct ValueSeqAccess(std::vec::IntoIter<Arc<Value<String>>>);
+
+impl <'de> de::SeqAccess<'de> for ValueSeqAccess {
+	type Error = ConfigError;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+		self.0.next().map(|value| seed.deserialize(ValueDeserializer(value))).transpose()
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.0.len())
+	}
+}
+
+/// Sequence of configuration paths.
+struct PathSeqAccess<'c> {
+	children: std::vec::IntoIter<Deserializer<'c>>
+}
+
+impl <'de, 'c> de::SeqAccess<'de> for PathSeqAccess<'c> {
+	type Error = ConfigError;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+		self.children.next().map(|child| seed.deserialize(child)).transpose()
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.children.len())
+	}
+}
+
+/// Map of the children of a configuration path.
+struct PathMapAccess<'p, 'c> {
+	parent: &'p Deserializer<'c>,
+	children: std::vec::IntoIter<(String, Vec<ConfPath>)>,
+	next: Option<(String, Vec<ConfPath>)>
+}
+
+impl <'de, 'p, 'c> de::MapAccess<'de> for PathMapAccess<'p, 'c> {
+	type Error = ConfigError;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+		match self.children.next() {
+			Some((name, keys)) => {
+				let key = seed.deserialize(name.as_str().into_deserializer()).map_err(|e| with_path(e, &self.parent.path.push(&name)))?;
+				self.next = Some((name, keys));
+				Ok(Some(key))
+			},
+			None => Ok(None)
+		}
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		let (name, keys) = self.next.take().ok_or_else(|| <ConfigError as de::Error>::custom("value requested before key"))?;
+		let child = self.parent.child(&name, keys);
+		let path = child.path.clone();
+
+		seed.deserialize(child).map_err(|e| with_path(e, &path))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.children.len())
+	}
+}
+
+/// Enum variant stored within a sub-path named like the variant.
+struct PathEnumAccess<'c> {
+	variant: String,
+	content: Deserializer<'c>
+}
+
+impl <'de, 'c> de::EnumAccess<'de> for PathEnumAccess<'c> {
+	type Error = ConfigError;
+	type Variant = Deserializer<'c>;
+
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+		let variant = seed.deserialize(self.variant.as_str().into_deserializer())?;
+
+		Ok((variant, self.content))
+	}
+}
+
+impl <'de, 'c> de::VariantAccess<'de> for Deserializer<'c> {
+	type Error = ConfigError;
+
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+		seed.deserialize(self)
+	}
+
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+		de::Deserializer::deserialize_seq(self, visitor)
+	}
+
+	fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+		de::Deserializer::deserialize_struct(self, "", fields, visitor)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sources::text::ConfigText;
+	use serde::Deserialize;
+	use std::collections::BTreeMap;
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	#[serde(rename_all = "lowercase")]
+	enum Mode {
+		Fast,
+		Safe
+	}
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Backend {
+		host: String,
+		port: u16
+	}
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Settings {
+		name: String,
+		enabled: bool,
+		ratio: f64,
+		tags: Vec<String>,
+		mode: Mode,
+		limits: BTreeMap<String, u32>,
+		backends: Vec<Backend>,
+		comment: Option<String>,
+		#[serde(default)]
+		retries: u8
+	}
+
+	fn prepare_test_config(config_file: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigText::new(config_file.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	#[test]
+	fn deserialize() {
+		let c = prepare_test_config(r#"
+name=test
+enabled=true
+ratio=0.5
+tags=a
+tags=b
+mode=safe
+[limits]
+cpu=4
+memory=512
+[backends.0]
+host=first
+port=1
+[backends.1]
+host=second
+port=2
+"#);
+
+		let settings: Settings = from_config(&c).unwrap();
+
+		assert_eq!(settings, Settings {
+			name: String::from("test"),
+			enabled: true,
+			ratio: 0.5,
+			tags: vec![String::from("a"), String::from("b")],
+			mode: Mode::Safe,
+			limits: [(String::from("cpu"), 4), (String::from("memory"), 512)].iter().cloned().collect(),
+			backends: vec![Backend { host: String::from("first"), port: 1 }, Backend { host: String::from("second"), port: 2 }],
+			comment: None,
+			retries: 0
+		});
+
+		assert!(c.unused_keys().is_empty());
+	}
+
+	#[test]
+	fn sub_path() {
+		let c = prepare_test_config("[backend]\nhost=localhost\nport=80\n");
+
+		let backend: Backend = from_config_path(&c, c.root().push("backend")).unwrap();
+		assert_eq!(backend, Backend { host: String::from("localhost"), port: 80 });
+	}
+
+	#[test]
+	fn value_error() {
+		let c = prepare_test_config("host=localhost\nport=http\n");
+
+		match from_config::<Backend>(&c) {
+			Err(ConfigError::ValueError(_, location)) => assert_eq!(location.to_string(), "conf:myfile:2"),
+			_ => panic!("Value error not detected.")
+		}
+	}
+
+	#[test]
+	fn unknown_variant() {
+		let c = prepare_test_config("mode=slow\n");
+
+		#[derive(Deserialize, Debug)]
+		struct ModeOnly {
+			#[allow(dead_code)]
+			mode: Mode
+		}
+
+		let error = from_config::<ModeOnly>(&c).unwrap_err();
+		assert_eq!(error.to_string(), "unknown variant `slow`, expected `fast` or `safe`@'conf:myfile:1'");
+	}
+
+	#[test]
+	fn keys_enumerated_once() {
+		use crate::source::Source;
+		use crate::item::StringItem;
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		struct CountingSource(Box<dyn Source>, Arc<AtomicUsize>);
+
+		impl Source for CountingSource {
+			fn get(&self, key: ConfPath) -> Option<StringItem> {
+				self.0.get(key)
+			}
+
+			fn keys(&self) -> Vec<ConfPath> {
+				self.1.fetch_add(1, Ordering::SeqCst);
+				self.0.keys()
+			}
+		}
+
+		let calls = Arc::new(AtomicUsize::new(0));
+		let mut c = Config::default();
+		c.add_source(Box::new(CountingSource(ConfigText::new("name=a\nenabled=true\nratio=1\ntags=t\nmode=fast\n[limits]\ncpu=1\n[backends.0]\nhost=h\nport=1\n".as_bytes(), "myfile").unwrap(), calls.clone())));
+
+		let settings: Settings = from_config(&c).unwrap();
+		assert_eq!(settings.backends.len(), 1);
+		assert_eq!(calls.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn unknown_fields() {
+		#[derive(Deserialize, Debug)]
+		#[serde(deny_unknown_fields)]
+		struct Strict {
+			#[allow(dead_code)]
+			host: String
+		}
+
+		let c = prepare_test_config("host=localhost\nhots=other\n");
+
+		let error = from_config::<Strict>(&c).unwrap_err();
+		assert_eq!(error.to_string(), "Deserializing config key 'hots' failed: unknown field `hots`, expected `host`");
+
+		// Without deny_unknown_fields the key is ignored and reported as unused.
+		let c = prepare_test_config("host=localhost\nport=80\nhots=other\n");
+		let backend: Backend = from_config(&c).unwrap();
+		assert_eq!(backend, Backend { host: String::from("localhost"), port: 80 });
+		assert_eq!(c.unused_keys().iter().map(|unused| unused.key().to_string()).collect::<Vec<_>>(), ["hots"]);
+	}
+
+	#[test]
+	fn missing_field() {
+		let c = prepare_test_config("[backend]\nhost=localhost\n");
+
+		let error = from_config_path::<Backend>(&c, c.root().push("backend")).unwrap_err();
+		assert_eq!(error.to_string(), "Deserializing config key 'backend' failed: missing field `port`");
+	}
+}
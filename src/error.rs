@@ -1,10 +1,49 @@
 //! General error enums.
+//!
+//! ## Reporting multiple errors
+//!
+//! Using `?` on every call to [`value`](crate::item::ValueExtractor::value)
+//! stops at the first invalid configuration value. The user fixes one error,
+//! restarts the application and hits the next one. To report all errors at
+//! once, the results of the configuration pipelines can be passed to a
+//! [`ConfigErrors`] collector. After all values are read,
+//! [`into_result`](ConfigErrors::into_result) returns all collected errors
+//! sorted by the source and line of their location.
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::error::ConfigErrors;
+//! # use justconfig::item::ValueExtractor;
+//! # use justconfig::validators::Range;
+//! # use justconfig::sources::text::ConfigText;
+//! #
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new("port=http\nworkers=100\n".as_bytes(), "myconfig.conf").unwrap());
+//!
+//! let mut errors = ConfigErrors::default();
+//! let workers: Option<u32> = errors.check(conf.get(conf.root().push("workers")).max(16).value());
+//! let port: Option<u16> = errors.check(conf.get(conf.root().push("port")).value());
+//! let host: Option<String> = errors.check(conf.get(conf.root().push("host")).value());
+//!
+//! let report = errors.into_result().unwrap_err();
+//! assert_eq!(report.len(), 3);
+//! assert_eq!(report.to_string(), "3 configuration errors:
+//!   invalid digit found in string@'conf:myconfig.conf:1'
+//!   must be <= 16.@'conf:myconfig.conf:2'
+//!   Missing value for config key 'host'.");
+//! ```
 use crate::item::SourceLocation;
 use crate::confpath::ConfPath;
-use std::rc::Rc;
+use crate::unused::UnusedKey;
+use std::sync::Arc;
+use std::fmt;
 
 /// Enum used to return errors from the pipeline.
+///
+/// New variants may be added in minor releases. Matches on this enum must
+/// contain a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ConfigError {
 	/// A required configuration value was not found.
 	ValueNotFound(ConfPath),
@@ -17,7 +56,7 @@ pub enum ConfigError {
 	/// [`SourceLocation`] trait. The first
 	/// parameter contains the maximum number of values this configuration item
 	/// can have.
-	TooManyValues(usize, ConfPath, Vec<Rc<dyn SourceLocation>>),
+	TooManyValues(usize, ConfPath, Vec<Arc<dyn SourceLocation>>),
 	/// If [`values()`](crate::item::ValueExtractor::values) is
 	/// called with a range restricting the valid number of values and there are
 	/// not enough values this error is returned. The first parameter is
@@ -29,13 +68,36 @@ pub enum ConfigError {
 	/// The location of the error is represented by an instance of a struct
 	/// implementing the [`SourceLocation']
 	/// trait.
-	ValueError(Box<dyn std::error::Error>, Rc<dyn SourceLocation>),
+	ValueError(Box<dyn std::error::Error + Send + Sync>, Arc<dyn SourceLocation>),
 	/// Is returned if the pipeline is not linear. This should never happen if
 	/// this library is used correctly.
-	MultipleReferences
+	MultipleReferences,
+	/// Returned by [`check_unused`](crate::Config::check_unused) if there are
+	/// configuration keys that were never requested. Contains an entry for
+	/// every unused key.
+	UnusedKeys(Vec<UnusedKey>),
+	/// Returned by [`reload`](crate::Config::reload) if a configuration source
+	/// could not be reloaded. The configuration source keeps its previous
+	/// configuration information.
+	ReloadFailed(Box<dyn std::error::Error + Send + Sync>),
+	/// Returned by the serde deserializer (see the `de` module) if the
+	/// configuration information does not match the structure of the
+	/// deserialized type. Contains the error message and the configuration
+	/// path the error occurred at, if it is known. The variant exists
+	/// regardless of the `serde` feature. This way the public API of
+	/// `ConfigError` does not depend on the enabled features.
+	Deserialize(String, Option<ConfPath>),
+	/// Returned by [`get`](crate::Config::get) if a configuration key and one
+	/// of its deprecated aliases or two of its deprecated aliases are both set.
+	/// The first parameter contains the requested key. The second and third
+	/// parameter contain the two keys that are set. If the requested key is
+	/// set, it is passed as the second parameter. The last parameter contains
+	/// the source locations of the values of both keys. See the
+	/// [`alias`](crate::alias) module.
+	AliasConflict(ConfPath, ConfPath, ConfPath, Vec<Arc<dyn SourceLocation>>)
 }
 
-fn too_many_values_formater(f: &mut std::fmt::Formatter, max_num: usize, key: &ConfPath, source_locations: &[Rc<dyn SourceLocation>]) -> std::fmt::Result {
+fn too_many_values_formater(f: &mut std::fmt::Formatter, max_num: usize, key: &ConfPath, source_locations: &[Arc<dyn SourceLocation>]) -> std::fmt::Result {
 	write!(f, "More than {} value found for key {}@[", max_num, key)?;
 	for (i, source_location) in source_locations.iter().enumerate() {
 		if i > 0 {
@@ -47,6 +109,31 @@ fn too_many_values_formater(f: &mut std::fmt::Formatter, max_num: usize, key: &C
 	write!(f, "]")
 }
 
+fn alias_conflict_formater(f: &mut std::fmt::Formatter, key: &ConfPath, first: &ConfPath, second: &ConfPath, source_locations: &[Arc<dyn SourceLocation>]) -> std::fmt::Result {
+	if first == key {
+		write!(f, "Config key '{}' and its deprecated alias '{}' are both set@[", key, second)?;
+	} else {
+		write!(f, "Deprecated aliases '{}' and '{}' of config key '{}' are both set@[", first, second, key)?;
+	}
+	for (i, source_location) in source_locations.iter().enumerate() {
+		if i > 0 {
+			write!(f, ", ")?;
+		}
+
+		write!(f, "'{}'", source_location)?;
+	};
+	write!(f, "]")
+}
+
+fn unused_keys_formater(f: &mut std::fmt::Formatter, unused_keys: &[UnusedKey]) -> std::fmt::Result {
+	write!(f, "Unknown config keys:")?;
+	for unused_key in unused_keys {
+		write!(f, "\n  {}", unused_key)?;
+	}
+
+	Ok(())
+}
+
 impl std::fmt::Display for ConfigError {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		match self {
@@ -54,7 +141,12 @@ impl std::fmt::Display for ConfigError {
 			Self::TooManyValues(max_num, key, source_locations) => too_many_values_formater(f, *max_num, key, source_locations),
 			Self::NotEnoughValues(min_num, key) => write!(f, "Key '{}' must have at least {} values.", key, min_num),
 			Self::ValueError(error, source_location) => write!(f, "{}@'{}'", error, source_location),
-			Self::MultipleReferences => write!(f, "Internal error. Multiple references to same config pipeline.")
+			Self::MultipleReferences => write!(f, "Internal error. Multiple references to same config pipeline."),
+			Self::UnusedKeys(unused_keys) => unused_keys_formater(f, unused_keys),
+			Self::ReloadFailed(error) => write!(f, "Reloading the configuration failed: {}", error),
+			Self::Deserialize(msg, Some(key)) => write!(f, "Deserializing config key '{}' failed: {}", key, msg),
+			Self::Deserialize(msg, None) => write!(f, "Deserializing the configuration failed: {}", msg),
+			Self::AliasConflict(key, first, second, source_locations) => alias_conflict_formater(f, key, first, second, source_locations)
 		}
 	}
 }
@@ -63,13 +155,182 @@ impl std::error::Error for ConfigError {
 	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
 		match self {
 			Self::ValueError(error, _) => Some(error.as_ref()),
+			Self::ReloadFailed(error) => Some(error.as_ref()),
 			_ => None
 		}
 	}
 }
 
 impl ConfigError {
-	pub fn from_error<E: std::error::Error + 'static>(error: E, source_location: Rc<dyn SourceLocation>) -> Self {
+	/// Returns the source location of the value that caused this error.
+	///
+	/// If the error is not caused by a specific value `None` is returned.
+	pub fn location(&self) -> Option<Arc<dyn SourceLocation>> {
+		match self {
+			Self::TooManyValues(_, _, source_locations) | Self::AliasConflict(_, _, _, source_locations) => source_locations.first().cloned(),
+			Self::ValueError(_, source_location) => Some(source_location.clone()),
+			_ => None
+		}
+	}
+
+	pub fn from_error<E: std::error::Error + Send + Sync + 'static>(error: E, source_location: Arc<dyn SourceLocation>) -> Self {
 		ConfigError::ValueError(Box::from(error), source_location)
 	}
 }
+
+/// Collects multiple configuration errors.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Default)]
+pub struct ConfigErrors {
+	errors: Vec<ConfigError>
+}
+
+impl ConfigErrors {
+	/// Records the error of a configuration pipeline.
+	///
+	/// If `result` contains a value, it is returned. Otherwise the error is
+	/// recorded and `None` is returned.
+	pub fn check<T>(&mut self, result: Result<T, ConfigError>) -> Option<T> {
+		match result {
+			Ok(value) => Some(value),
+			Err(error) => {
+				self.errors.push(error);
+				None
+			}
+		}
+	}
+
+	/// Records an error.
+	pub fn push(&mut self, error: ConfigError) {
+		self.errors.push(error);
+	}
+
+	/// Returns `true` if no errors were recorded.
+	pub fn is_empty(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// Returns the number of recorded errors.
+	pub fn len(&self) -> usize {
+		self.errors.len()
+	}
+
+	/// Returns an iterator over the recorded errors.
+	///
+	/// After calling [`into_result`](Self::into_result) the errors are sorted
+	/// by their source location.
+	pub fn iter(&self) -> impl Iterator<Item=&ConfigError> {
+		self.errors.iter()
+	}
+
+	/// Returns `Ok(())` if no errors were recorded. Otherwise all recorded
+	/// errors are returned.
+	///
+	/// The errors are sorted by the [`source_name`](SourceLocation::source_name)
+	/// and [`line`](SourceLocation::line) of their location. Errors without a
+	/// location are placed after all other errors in the order they were
+	/// recorded.
+	pub fn into_result(mut self) -> Result<(), Self> {
+		if self.errors.is_empty() {
+			Ok(())
+		} else {
+			self.errors.sort_by_cached_key(|error| match error.location() {
+				Some(location) => (false, location.source_name().map(String::from), location.line()),
+				None => (true, None, None)
+			});
+
+			Err(self)
+		}
+	}
+}
+
+impl From<ConfigError> for ConfigErrors {
+	fn from(error: ConfigError) -> Self {
+		Self {
+			errors: vec![error]
+		}
+	}
+}
+
+impl IntoIterator for ConfigErrors {
+	type Item = ConfigError;
+	type IntoIter = std::vec::IntoIter<ConfigError>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.errors.into_iter()
+	}
+}
+
+impl fmt::Display for ConfigErrors {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.errors.len() == 1 {
+			write!(f, "1 configuration error:")?;
+		} else {
+			write!(f, "{} configuration errors:", self.errors.len())?;
+		}
+
+		for error in self.errors.iter() {
+			// Indent multi line error messages.
+			write!(f, "\n  {}", error.to_string().replace('\n', "\n  "))?;
+		}
+
+		Ok(())
+	}
+}
+
+impl std::error::Error for ConfigErrors {
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::item::ValueExtractor;
+	use crate::sources::text::ConfigText;
+	use crate::sources::defaults::Defaults;
+
+	#[test]
+	fn check() {
+		let mut errors = ConfigErrors::default();
+
+		assert_eq!(errors.check(Ok::<u32, ConfigError>(5)), Some(5));
+		assert!(errors.is_empty());
+		assert!(errors.into_result().is_ok());
+	}
+
+	#[test]
+	fn sorted() {
+		let mut c = Config::default();
+		c.add_source(ConfigText::new("b=x\nc=1\nc=2\n".as_bytes(), "file2").unwrap());
+		c.add_source(ConfigText::new("\n\na=x\n".as_bytes(), "file1").unwrap());
+		let mut defaults = Defaults::default();
+		defaults.set(c.root().push("d"), "x", "fallback");
+		c.add_source(defaults);
+
+		let mut errors = ConfigErrors::default();
+		errors.check(c.get(c.root().push("missing")).value() as Result<String, ConfigError>);
+		errors.check(c.get(c.root().push("d")).value() as Result<u32, ConfigError>);
+		errors.check(c.get(c.root().push("c")).value() as Result<u32, ConfigError>);
+		errors.check(c.get(c.root().push("a")).value() as Result<u32, ConfigError>);
+		errors.check(c.get(c.root().push("b")).value() as Result<u32, ConfigError>);
+
+		let errors = errors.into_result().unwrap_err();
+		assert_eq!(errors.iter().map(|e| e.location().map(|l| l.to_string())).collect::<Vec<_>>(), [
+			Some(String::from("default from fallback")),
+			Some(String::from("conf:file1:3")),
+			Some(String::from("conf:file2:1")),
+			Some(String::from("conf:file2:2")),
+			None
+		]);
+	}
+
+	#[test]
+	fn display() {
+		let mut errors = ConfigErrors::from(ConfigError::MultipleReferences);
+		assert_eq!(errors.to_string(), "1 configuration error:\n  Internal error. Multiple references to same config pipeline.");
+
+		errors.push(ConfigError::NotEnoughValues(2, ConfPath::from(&["a"])));
+		assert_eq!(errors.to_string(), "2 configuration errors:\n  Internal error. Multiple references to same config pipeline.\n  Key 'a' must have at least 2 values.");
+	}
+}
@@ -0,0 +1,244 @@
+//! Explains where the value of a configuration item is coming from.
+//!
+//! When multiple configuration sources are layered it is not always obvious
+//! why a configuration item has a specific value. The
+//! [`explain`](crate::Config::explain) method queries every registered
+//! configuration source for a key and returns an [`Explanation`]. The
+//! explanation lists every source that knows about the key in the order the
+//! sources are queried by [`get`](crate::Config::get) and marks the source that
+//! supplied the value.
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::ConfPath;
+//! # use justconfig::sources::defaults::Defaults;
+//! #
+//! let mut conf = Config::default();
+//!
+//! let mut overrides = Defaults::default();
+//! overrides.set(conf.root().push("port"), "8080", "command line");
+//! conf.add_source(overrides);
+//!
+//! let mut defaults = Defaults::default();
+//! defaults.set(conf.root().push("port"), "80", "default port");
+//! conf.add_source(defaults);
+//!
+//! let explanation = conf.explain(conf.root().push("port"));
+//!
+//! assert_eq!(explanation.candidates().len(), 2);
+//! assert_eq!(explanation.winner().unwrap().source_index(), 0);
+//!
+//! // Print a human readable representation of the explanation
+//! println!("{}", explanation);
+//! ```
+use crate::confpath::ConfPath;
+use crate::item::StringItem;
+use crate::merge::MergePolicy;
+use std::fmt;
+
+/// A configuration source that returned a value for the explained key.
+pub struct Candidate {
+	source_index: usize,
+	item: StringItem
+}
+
+impl Candidate {
+	pub(crate) fn new(source_index: usize, item: StringItem) -> Self {
+		Self {
+			source_index,
+			item
+		}
+	}
+
+	/// Returns the index of the configuration source that returned this
+	/// candidate.
+	///
+	/// The index is the position of the source in the order the sources were
+	/// registered by calling [`add_source`](crate::Config::add_source).
+	pub fn source_index(&self) -> usize {
+		self.source_index
+	}
+
+	/// Returns the configuration item returned by the configuration source.
+	///
+	/// The values of the item and their source locations can be inspected by
+	/// calling [`values`](StringItem::values).
+	pub fn item(&self) -> &StringItem {
+		&self.item
+	}
+}
+
+/// Result of a call to [`explain`](crate::Config::explain).
+pub struct Explanation {
+	key: ConfPath,
+	profile: Option<String>,
+	policy: MergePolicy,
+	candidates: Vec<Candidate>,
+	contributors: Vec<usize>
+}
+
+impl Explanation {
+	pub(crate) fn new(key: ConfPath, profile: Option<String>, policy: MergePolicy, candidates: Vec<Candidate>) -> Self {
+		let contributors = policy.select(&candidates);
+
+		Self {
+			key,
+			profile,
+			policy,
+			candidates,
+			contributors
+		}
+	}
+
+	/// Returns the configuration path that was explained.
+	pub fn key(&self) -> &ConfPath {
+		&self.key
+	}
+
+	/// Returns the name of the active profile that supplied the value.
+	///
+	/// If the value is not supplied by a profile `None` is returned. In this
+	/// case the candidates are the sources that know about the key itself.
+	/// Otherwise the candidates are the sources that know about the key within
+	/// the profile. See the [`profile`](mod@crate::profile) module.
+	pub fn profile(&self) -> Option<&str> {
+		self.profile.as_deref()
+	}
+
+	/// Returns the [`MergePolicy`] that was used to combine the values of
+	/// the candidates.
+	pub fn policy(&self) -> MergePolicy {
+		self.policy
+	}
+
+	/// Returns all configuration sources that returned a value for the key.
+	///
+	/// The candidates are returned in priority order. The first candidate is
+	/// the one with the highest priority.
+	pub fn candidates(&self) -> &[Candidate] {
+		&self.candidates
+	}
+
+	/// Returns the candidates that supplied the values returned by
+	/// [`get`](crate::Config::get).
+	///
+	/// The candidates are returned in the order their values are combined
+	/// by the [`MergePolicy`].
+	pub fn contributors(&self) -> impl Iterator<Item=&Candidate> {
+		self.contributors.iter().map(move |index| &self.candidates[*index])
+	}
+
+	/// Returns the candidate that supplied the value returned by
+	/// [`get`](crate::Config::get).
+	///
+	/// If the values of multiple candidates are combined, the candidate whose
+	/// values come first is returned. If no configuration source knows about
+	/// the key, `None` is returned.
+	pub fn winner(&self) -> Option<&Candidate> {
+		self.contributors().next()
+	}
+
+	/// Returns the candidates that are shadowed by the contributing
+	/// candidates.
+	pub fn shadowed(&self) -> impl Iterator<Item=&Candidate> {
+		self.candidates.iter().enumerate().filter(move |(index, _)| !self.contributors.contains(index)).map(|(_, candidate)| candidate)
+	}
+}
+
+impl fmt::Display for Explanation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.candidates.is_empty() {
+			return write!(f, "Config key '{}' is not set by any source.", self.key);
+		}
+
+		match (&self.profile, self.policy == MergePolicy::default()) {
+			(None, true) => write!(f, "Config key '{}':", self.key)?,
+			(None, false) => write!(f, "Config key '{}' (merged by {}):", self.key, self.policy)?,
+			(Some(profile), true) => write!(f, "Config key '{}' (profile '{}'):", self.key, profile)?,
+			(Some(profile), false) => write!(f, "Config key '{}' (profile '{}', merged by {}):", self.key, profile, self.policy)?
+		}
+
+		for (index, candidate) in self.candidates.iter().enumerate() {
+			let state = if self.contributors.contains(&index) { "used" } else { "shadowed" };
+
+			write!(f, "\n  source #{} ({}):", candidate.source_index, state)?;
+			if candidate.item.values().is_empty() {
+				write!(f, " no values")?;
+			}
+
+			for value in candidate.item.values() {
+				write!(f, "\n    '{}'@'{}'", value.value(), value.source())?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::Config;
+	use crate::ConfPath;
+	use crate::sources::defaults::Defaults;
+
+	fn prepare_test_config() -> Config {
+		let mut c = Config::default();
+
+		let mut first = Defaults::default();
+		first.set(c.root().push("a"), "a1", "first");
+		c.add_source(first);
+
+		let mut second = Defaults::default();
+		second.set(c.root().push("a"), "a2.1", "second.1");
+		second.put(c.root().push("a"), "a2.2", "second.2");
+		second.set(c.root().push("b"), "b2", "second");
+		c.add_source(second);
+
+		let mut third = Defaults::default();
+		third.empty(c.root().push("a"));
+		c.add_source(third);
+
+		c
+	}
+
+	#[test]
+	fn explain_shadowed() {
+		let c = prepare_test_config();
+		let explanation = c.explain(ConfPath::from(&["a"]));
+
+		assert_eq!(explanation.candidates().iter().map(|c| c.source_index()).collect::<Vec<_>>(), [0, 1, 2]);
+		assert_eq!(explanation.winner().unwrap().source_index(), 0);
+		assert_eq!(explanation.shadowed().map(|c| c.source_index()).collect::<Vec<_>>(), [1, 2]);
+
+		let second = &explanation.candidates()[1];
+		assert_eq!(second.item().values().iter().map(|v| v.value().as_str()).collect::<Vec<_>>(), ["a2.1", "a2.2"]);
+		assert_eq!(second.item().values().iter().map(|v| v.source().to_string()).collect::<Vec<_>>(), ["default from second.1", "default from second.2"]);
+	}
+
+	#[test]
+	fn explain_single() {
+		let c = prepare_test_config();
+		let explanation = c.explain(ConfPath::from(&["b"]));
+
+		assert_eq!(explanation.candidates().len(), 1);
+		assert_eq!(explanation.winner().unwrap().source_index(), 1);
+		assert_eq!(explanation.shadowed().count(), 0);
+	}
+
+	#[test]
+	fn explain_missing() {
+		let c = prepare_test_config();
+		let explanation = c.explain(ConfPath::from(&["c"]));
+
+		assert!(explanation.winner().is_none());
+		assert_eq!(explanation.candidates().len(), 0);
+		assert_eq!(format!("{}", explanation), "Config key 'c' is not set by any source.");
+	}
+
+	#[test]
+	fn explain_display() {
+		let c = prepare_test_config();
+
+		assert_eq!(format!("{}", c.explain(ConfPath::from(&["a"]))), "Config key 'a':\n  source #0 (used):\n    'a1'@'default from first'\n  source #1 (shadowed):\n    'a2.1'@'default from second.1'\n    'a2.2'@'default from second.2'\n  source #2 (shadowed): no values");
+	}
+}
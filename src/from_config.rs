@@ -0,0 +1,54 @@
+//! Populating typed configuration structs.
+//!
+//! Most applications read their configuration into a struct. Reading every
+//! field by hand results in long functions chaining
+//! [`get`](crate::Config::get), processors and validators for every field.
+//!
+//! The [`FromConfig`] trait is implemented by types that can be read from the
+//! configuration. With the `derive` feature enabled the trait can be derived
+//! for structs by using `#[derive(JustConfig)]`. The derived implementation
+//! drives the normal configuration pipeline for every field. See the
+//! documentation of the [`justconfig_derive`](https://docs.rs/justconfig_derive)
+//! crate for the supported attributes.
+//!
+//! The trait can be implemented by hand, too:
+//!
+//! ```rust
+//! use justconfig::{Config, ConfPath, FromConfig};
+//! use justconfig::error::ConfigError;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::text::ConfigText;
+//!
+//! struct Database {
+//!   host: String,
+//!   port: u16
+//! }
+//!
+//! impl FromConfig for Database {
+//!   fn from_config(config: &Config, path: ConfPath) -> Result<Self, ConfigError> {
+//!     Ok(Self {
+//!       host: config.get(path.push("host")).value()?,
+//!       port: config.get(path.push("port")).try_value()?.unwrap_or(5432)
+//!     })
+//!   }
+//! }
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new("[db]\nhost=localhost\n".as_bytes(), "myconfig.conf").unwrap());
+//!
+//! let db = Database::from_config(&conf, conf.root().push("db")).unwrap();
+//! assert_eq!(db.host, "localhost");
+//! assert_eq!(db.port, 5432);
+//! ```
+use crate::Config;
+use crate::confpath::ConfPath;
+use crate::error::ConfigError;
+
+/// Trait implemented by types that can be read from the configuration.
+pub trait FromConfig: Sized {
+	/// Reads the value from the configuration.
+	///
+	/// The `path` parameter contains the configuration path the value is read
+	/// from. Structs read their fields from sub-paths of this path.
+	fn from_config(config: &Config, path: ConfPath) -> Result<Self, ConfigError>;
+}
@@ -63,7 +63,7 @@
 use crate::confpath::ConfPath;
 use crate::error::ConfigError;
 use std::str::FromStr;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::convert::TryInto;
 use std::error::Error;
 use std::ops::RangeBounds;
@@ -72,7 +72,26 @@ use std::ops::RangeBounds;
 ///
 /// This trait is used to provide the source of a configuration entry, for
 /// example, for use in error messages.
-pub trait SourceLocation : std::fmt::Display + std::fmt::Debug {}
+pub trait SourceLocation : std::fmt::Display + std::fmt::Debug + Send + Sync {
+	/// Returns the name of the source (for example the file name) this
+	/// location points into.
+	///
+	/// This is used to sort error reports (see
+	/// [`ConfigErrors`](crate::error::ConfigErrors)). Implementing this
+	/// method is optional. The default implementation returns `None`.
+	fn source_name(&self) -> Option<&str> {
+		None
+	}
+
+	/// Returns the line number this location points to.
+	///
+	/// This is used to sort error reports (see
+	/// [`ConfigErrors`](crate::error::ConfigErrors)). Implementing this
+	/// method is optional. The default implementation returns `None`.
+	fn line(&self) -> Option<usize> {
+		None
+	}
+}
 
 /// Structure representing a configuration value.
 ///
@@ -86,7 +105,7 @@ pub trait SourceLocation : std::fmt::Display + std::fmt::Debug {}
 /// See [`Item`](crate::item) for more Information.
 pub struct Value<T> {
 	value: T,
-	source: Rc<dyn SourceLocation>
+	source: Arc<dyn SourceLocation>
 }
 
 impl <T> Value<T> {
@@ -94,23 +113,28 @@ impl <T> Value<T> {
 	///
 	/// Configuration values are normally created to be included into configuration
 	/// [`Item`](crate::item)s.
-	pub fn new(value: T, source: Rc<dyn SourceLocation>) -> Rc<Self> {
-		Rc::new(Self {
+	pub fn new(value: T, source: Arc<dyn SourceLocation>) -> Arc<Self> {
+		Arc::new(Self {
 			value,
 			source
 		})
 	}
 
 	/// Returns the source of this configuration value.
-	pub fn source(&self) -> Rc<dyn SourceLocation>{
+	pub fn source(&self) -> Arc<dyn SourceLocation>{
 		self.source.clone()
 	}
+
+	/// Returns a reference to the configuration value.
+	pub fn value(&self) -> &T {
+		&self.value
+	}
 }
 
 #[derive(Clone)]
 struct Item<T> {
 	key: ConfPath,
-	values: Vec<Rc<Value<T>>>
+	values: Vec<Arc<Value<T>>>
 }
 
 /// Newtype for Items while they are passed though the processors of the config
@@ -133,14 +157,14 @@ impl StringItem {
 		})
 	}
 
-	pub(crate) fn from(key: ConfPath, values: &[Rc<Value<String>>]) -> Self {
+	pub(crate) fn from(key: ConfPath, values: &[Arc<Value<String>>]) -> Self {
 		Self (Item {
 			key,
 			values: Vec::from(values)
 		})
 	}
 
-	pub(crate) fn push(&mut self, new_value: Rc<Value<String>>) {
+	pub(crate) fn push(&mut self, new_value: Arc<Value<String>>) {
 		self.0.values.push(new_value);
 	}
 
@@ -148,6 +172,20 @@ impl StringItem {
 		self.0.values.clear();
 		self
 	}
+
+	/// Returns the configuration path of this item.
+	pub fn key(&self) -> &ConfPath {
+		&self.0.key
+	}
+
+	/// Returns the configuration values of this item.
+	///
+	/// Every value carries its [`SourceLocation`]. This can be used to inspect
+	/// the values returned by a configuration source before they are passed
+	/// down the configuration pipeline.
+	pub fn values(&self) -> &[Arc<Value<String>>] {
+		&self.0.values
+	}
 }
 
 /// Newtype for Items while they are passed though the validators of the config
@@ -158,7 +196,7 @@ impl StringItem {
 pub struct TypedItem<T: FromStr>(Item<T>);
 
 impl <T: FromStr> TypedItem<T> {
-	pub(crate) fn new(key: ConfPath, values: Vec<Rc<Value<T>>>) -> Self {
+	pub(crate) fn new(key: ConfPath, values: Vec<Arc<Value<T>>>) -> Self {
 		Self(Item {
 			key,
 			values
@@ -167,7 +205,7 @@ impl <T: FromStr> TypedItem<T> {
 }
 
 impl <T: FromStr> TypedItem<T> {
-	pub fn filter(self, filter: impl Fn(&T) -> Result<(), Box<dyn Error>>) -> Result<Self, ConfigError> {
+	pub fn filter(self, filter: impl Fn(&T) -> Result<(), Box<dyn Error + Send + Sync>>) -> Result<Self, ConfigError> {
 		for v in self.0.values.iter() {
 			filter(&v.value).map_err(|e| ConfigError::ValueError(e, v.source.clone()))?;
 		}
@@ -180,7 +218,7 @@ pub enum MapAction {
 	Keep,
 	Replace(Vec<String>),
 	Drop,
-	Fail(Box<dyn Error>)
+	Fail(Box<dyn Error + Send + Sync>)
 }
 
 impl StringItem {
@@ -200,14 +238,14 @@ impl StringItem {
 	}
 }
 
-impl <T: FromStr> TryInto<TypedItem<T>> for Result<StringItem, ConfigError> where T::Err: Error + 'static {
+impl <T: FromStr> TryInto<TypedItem<T>> for Result<StringItem, ConfigError> where T::Err: Error + Send + Sync + 'static {
 	type Error = ConfigError;
 
 	fn try_into(self) -> Result<TypedItem<T>, ConfigError> {
 		let s = self?;
 
 		// Iterate all String-Values...
-		let typed_values: Result<Vec<Rc<Value<T>>>, ConfigError> = s.0.values.into_iter().map(|v|
+		let typed_values: Result<Vec<Arc<Value<T>>>, ConfigError> = s.0.values.into_iter().map(|v|
 			// ...and convert them to T...
 			v.value.parse::<T>().map(|nv|
 				Value::new(nv, v.source.clone())
@@ -341,7 +379,7 @@ fn values_out_of_range<T: FromStr, R: RangeBounds<usize>>(mut item: TypedItem<T>
 	let num_items = item.0.values.len();
 
 	if range.contains(&num_items) {
-		item.0.values.drain(..).map(|r| Rc::try_unwrap(r).map(|v| v.value).map_err(|_| ConfigError::MultipleReferences)).collect()
+		item.0.values.drain(..).map(|r| Arc::try_unwrap(r).map(|v| v.value).map_err(|_| ConfigError::MultipleReferences)).collect()
 	} else {
 		// The number of items is not part of the range. Check if the upper or lower bound
 		// was violated.
@@ -365,7 +403,7 @@ fn values_out_of_range<T: FromStr, R: RangeBounds<usize>>(mut item: TypedItem<T>
 		} else if upper_limit_excl.is_some() && (num_items >= upper_limit_excl.unwrap()) {
 			// Upper bound violated
 			let first_surplus_index = upper_limit_excl.unwrap().saturating_sub(1);
-			let surplus_sources = item.0.values.drain(first_surplus_index..).map(|r| Rc::try_unwrap(r).map(|v| v.source).map_err(|_| ConfigError::MultipleReferences)).collect::<Result<Vec<Rc<dyn SourceLocation>>, ConfigError>>()?;
+			let surplus_sources = item.0.values.drain(first_surplus_index..).map(|r| Arc::try_unwrap(r).map(|v| v.source).map_err(|_| ConfigError::MultipleReferences)).collect::<Result<Vec<Arc<dyn SourceLocation>>, ConfigError>>()?;
 
 			Err(ConfigError::TooManyValues(first_surplus_index, item.0.key, surplus_sources))
 		} else {
@@ -388,7 +426,7 @@ impl <T: FromStr> ValueExtractor<T> for Result<TypedItem<T>, ConfigError> {
 
 		match ci.values.len() {
 			0 => Err(ConfigError::ValueNotFound(ci.key)),
-			1 => Rc::try_unwrap(ci.values.pop().unwrap()).map(|v| v.value).map_err(|_| ConfigError::MultipleReferences),
+			1 => Arc::try_unwrap(ci.values.pop().unwrap()).map(|v| v.value).map_err(|_| ConfigError::MultipleReferences),
 			_ => Err(ConfigError::TooManyValues(1, ci.key, ci.values.iter().map(|v| v.source()).collect()))
 		}
 	}
@@ -404,7 +442,7 @@ impl <T: FromStr> ValueExtractor<T> for Result<TypedItem<T>, ConfigError> {
 	}
 }
 
-impl <T: FromStr> ValueExtractor<T> for Result<StringItem, ConfigError> where T::Err: Error + 'static {
+impl <T: FromStr> ValueExtractor<T> for Result<StringItem, ConfigError> where T::Err: Error + Send + Sync + 'static {
 	fn try_value(self) -> Result<Option<T>, ConfigError> {
 		(self.try_into() as Result<TypedItem<T>, ConfigError>).try_value()
 	}
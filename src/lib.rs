@@ -158,6 +158,27 @@
 //!
 //! ## Enumerating keys
 //!
+//! The keys of the merged configuration can be listed by calling
+//! [`keys`](Config::keys) or [`keys_under`](Config::keys_under). These methods
+//! return the union of the keys of all configuration sources that implement
+//! the [`keys`](Source::keys) method of the [`Source`] trait. All sources
+//! included with just-config implement it.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::sources::text::ConfigText;
+//!
+//! let mut conf = Config::default();
+//!
+//! let config_file = "[db]\nhost=localhost\nport=5432\n";
+//! conf.add_source(ConfigText::new(config_file.as_bytes(), "myconfig.conf").expect("Loading configuration file failed."));
+//!
+//! for key in conf.keys_under(&conf.root().push("db")) {
+//!     println!("{}", key);
+//! }
+//! ```
+//!
 //! Every `ConfPath` keeps track of all items that where created using it. That
 //! way configuration sources can create a list of configuration items that can
 //! be enumerated. The [`ConfigText` source](sources::text) offers the
@@ -193,9 +214,107 @@
 //! supplying a configuration file in `/etc`. The
 //! [`stack_config`](sources::text::stack_config) function makes
 //! this kind of configuration easy to implement by containing all the necessary
-//! boilerplate code.
+//! boilerplate code. Files of other formats can be stacked by using the
+//! generic [`stack_config`](sources::stack_config) function.
+//!
+//! ## Reading configuration structs
+//!
+//! Instead of reading every configuration value by hand, a struct can
+//! implement the [`FromConfig`] trait. If the `derive` feature is enabled, the
+//! trait can be derived by using `#[derive(JustConfig)]`. See the
+//! [`from_config`] module for details.
+//!
+//! Applications using serde can deserialize their configuration structs
+//! directly from a [`Config`] by enabling the `serde` feature. See the
+//! `de` module for details.
+//!
+//! ## Merging values of multiple sources
+//!
+//! Normally the first configuration source that knows about a key supplies
+//! all values. The [`merge`] module describes how the values of multiple
+//! configuration sources can be combined instead.
+//!
+//! ## Reloading the configuration
+//!
+//! Long running processes can pick up configuration changes by calling
+//! [`reload`](Config::reload). Configuration files wrapped into a
+//! [`Reloadable`](sources::reloadable::Reloadable) source are reread if they
+//! changed and registered listeners are notified about the changed values.
+//! See the [`reload`] module for details.
+//!
+//! ## Scoped configuration
+//!
+//! Components that only need a part of the configuration can be passed a
+//! [`ConfigView`] created by [`scope`](Config::scope). The view resolves all
+//! configuration paths relative to a prefix. See the [`view`] module for
+//! details.
+//!
+//! ## Profiles
+//!
+//! Settings for different environments can be stored within profiles like
+//! `profile.dev` and `profile.prod`. After activating profiles by calling
+//! [`set_profiles`](Config::set_profiles) the settings of the active profiles
+//! take precedence. See the [`profile`] module for details.
+//!
+//! ## Renaming configuration keys
+//!
+//! Old names of renamed configuration keys can be registered as aliases by
+//! calling [`add_alias`](Config::add_alias). Their values are used if the new
+//! key is not set and a deprecation warning is recorded. See the [`alias`]
+//! module for details.
+//!
+//! ## Detecting unused keys
+//!
+//! To detect typos within configuration files the [`Config`] struct keeps
+//! track of all keys that were requested by the application. Calling
+//! [`check_unused`](Config::check_unused) after reading the configuration
+//! returns an error for every configuration key that was never requested. See
+//! the [`unused`] module for details.
+//!
+//! ## Finding the source of a value
+//!
+//! If multiple configuration sources are layered it is sometimes hard to tell
+//! why a configuration item has a specific value. The
+//! [`explain`](Config::explain) method lists every configuration source that
+//! knows about a key and shows which one supplied the value. See the
+//! [`explain`] module for details.
+//!
+//! ## Sharing the configuration between threads
+//!
+//! [`Config`], [`ConfPath`] and [`ConfigError`](error::ConfigError) are
+//! `Send` and `Sync`. A `Config` can be wrapped into an `Arc` and shared with
+//! worker threads or async tasks. Configuration errors can cross thread
+//! boundaries and be converted into boxed errors like
+//! `Box<dyn Error + Send + Sync>`.
+//!
+//! All configuration sources must implement `Send` and `Sync`, too.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::defaults::Defaults;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let mut conf = Config::default();
+//!
+//! let mut defaults = Defaults::default();
+//! defaults.set(conf.root().push("workers"), "4", "default");
+//! conf.add_source(defaults);
+//!
+//! let conf = Arc::new(conf);
+//!
+//! let worker_conf = conf.clone();
+//! let workers = thread::spawn(move || -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+//!   Ok(worker_conf.get(worker_conf.root().push("workers")).value()?)
+//! }).join().unwrap().unwrap();
+//!
+//! assert_eq!(workers, 4);
+//! ```
 
 use std::default::Default;
+use std::sync::{Mutex, PoisonError};
+use std::collections::HashSet;
 
 pub mod item;
 use item::StringItem;
@@ -214,10 +333,48 @@ pub mod sources;
 pub mod validators;
 pub mod processors;
 
+pub mod explain;
+use explain::{Candidate, Explanation};
+
+pub mod unused;
+use unused::UnusedKey;
+
+pub mod merge;
+use merge::MergePolicy;
+
+pub mod reload;
+use reload::Change;
+
+pub mod alias;
+use alias::Deprecation;
+
+pub mod view;
+use view::ConfigView;
+
+pub mod profile;
+
+pub mod from_config;
+pub use from_config::FromConfig;
+
+#[cfg(feature = "derive")]
+pub use justconfig_derive::JustConfig;
+
+#[cfg(feature = "serde")]
+pub mod de;
+
+/// Listener notified about configuration changes by [`Config::reload`].
+type ChangeListener = dyn Fn(&[Change]) + Send + Sync;
+
 /// Main struct representing a loaded configuration.
 pub struct Config {
 	sources: Vec<Box<dyn Source>>,
-	path_root: ConfPath
+	path_root: ConfPath,
+	used_keys: Mutex<HashSet<ConfPath>>,
+	merge_policies: Vec<(ConfPath, MergePolicy)>,
+	change_listeners: Vec<Box<ChangeListener>>,
+	aliases: Vec<(ConfPath, ConfPath)>,
+	deprecations: Mutex<Vec<Deprecation>>,
+	profiles: Vec<String>
 }
 
 impl Default for Config {
@@ -225,7 +382,13 @@ impl Default for Config {
 	fn default() -> Self {
 		Self {
 			sources: Vec::default(),
-			path_root: ConfPath::default()
+			path_root: ConfPath::default(),
+			used_keys: Mutex::new(HashSet::default()),
+			merge_policies: Vec::default(),
+			change_listeners: Vec::default(),
+			aliases: Vec::default(),
+			deprecations: Mutex::new(Vec::default()),
+			profiles: Vec::default()
 		}
 	}
 }
@@ -237,6 +400,8 @@ impl Config {
 	/// Multiple configuration sources can be added and are queried from first to last.
 	/// The first configuration source that returns values for a configuration item will be used.
 	/// All following configuration sources will be ignored for this configuration item.
+	/// This behaviour can be changed by setting a [`MergePolicy`] via
+	/// [`set_merge_policy`](Self::set_merge_policy).
 	///
 	/// ## Example
 	///
@@ -277,11 +442,389 @@ impl Config {
 		self.path_root.clone()
 	}
 
+	/// Returns a view of the configuration items below the passed path.
+	///
+	/// All configuration paths passed to the returned [`ConfigView`] are
+	/// relative to `path`. For details see the [`view`](mod@crate::view) module.
+	pub fn scope(&self, path: ConfPath) -> ConfigView<'_> {
+		ConfigView::new(self, path)
+	}
+
 	/// Get the configuration value identified by the passed `ConfPath`.
 	///
 	/// This method is the root of every configuration pipeline. For usage examples
 	/// see the [crates documentation](crate).
+	///
+	/// Every key passed to this method is recorded as used. See
+	/// [`unused_keys`](Self::unused_keys) for details.
+	///
+	/// If aliases are registered for the key, their values are used if the key
+	/// itself is not set. See [`add_alias`](Self::add_alias) for details.
 	pub fn get(&self, key: ConfPath) -> Result<StringItem, ConfigError> {
-		self.sources.iter().find_map(|source| source.get(key.clone())).ok_or(ConfigError::ValueNotFound(key))
+		let aliases: Vec<&ConfPath> = self.aliases.iter().filter(|(_, new_key)| *new_key == key).map(|(old_key, _)| old_key).collect();
+
+		{
+			let mut used_keys = self.used_keys.lock().unwrap_or_else(PoisonError::into_inner);
+			used_keys.insert(key.clone());
+			used_keys.extend(aliases.iter().map(|&old_key| old_key.clone()));
+		}
+
+		if aliases.is_empty() {
+			self.resolve(key)
+		} else {
+			self.resolve_aliased(key, &aliases)
+		}
+	}
+
+	/// Resolves a key that has aliases. The value of the key or of exactly one
+	/// of its aliases is returned.
+	fn resolve_aliased(&self, key: ConfPath, aliases: &[&ConfPath]) -> Result<StringItem, ConfigError> {
+		let mut found = match self.resolve(key.clone()) {
+			Ok(item) => Some((key.clone(), item)),
+			Err(ConfigError::ValueNotFound(_)) => None,
+			Err(error) => return Err(error)
+		};
+
+		for &old_key in aliases {
+			let old_item = match self.resolve(old_key.clone()) {
+				Ok(item) => item,
+				Err(ConfigError::ValueNotFound(_)) => continue,
+				Err(error) => return Err(error)
+			};
+
+			if let Some((found_key, item)) = found {
+				let locations = item.values().iter().chain(old_item.values()).map(|value| value.source()).collect();
+				return Err(ConfigError::AliasConflict(key, found_key, old_key.clone(), locations));
+			}
+
+			found = Some((old_key.clone(), old_item));
+		}
+
+		match found {
+			Some((found_key, item)) if found_key != key => {
+				let mut deprecations = self.deprecations.lock().unwrap_or_else(PoisonError::into_inner);
+				if !deprecations.iter().any(|deprecation| *deprecation.old_key() == found_key) {
+					deprecations.push(Deprecation::new(found_key, key.clone(), item.values().iter().map(|value| value.source()).collect()));
+				}
+
+				Ok(StringItem::from(key, item.values()))
+			},
+			Some((_, item)) => Ok(item),
+			None => Err(ConfigError::ValueNotFound(key))
+		}
+	}
+
+	/// Resolves the key within the active profiles first. If no profile sets
+	/// the key, the key itself is resolved.
+	fn resolve(&self, key: ConfPath) -> Result<StringItem, ConfigError> {
+		let policy = self.merge_policy(&key);
+
+		for (_, profile_key) in self.profile_keys(&key) {
+			match self.resolve_layer(profile_key, policy) {
+				Ok(item) => return Ok(StringItem::from(key, item.values())),
+				Err(ConfigError::ValueNotFound(_)) => continue,
+				Err(error) => return Err(error)
+			}
+		}
+
+		self.resolve_layer(key, policy)
+	}
+
+	/// Queries the configuration sources and merges the returned items
+	/// according to the passed merge policy.
+	fn resolve_layer(&self, key: ConfPath, policy: MergePolicy) -> Result<StringItem, ConfigError> {
+		if policy == MergePolicy::FirstWins {
+			// Shortcut for the default policy. There is no need to query all sources.
+			return self.sources.iter().find_map(|source| source.get(key.clone())).ok_or(ConfigError::ValueNotFound(key));
+		}
+
+		let candidates = self.candidates(&key);
+		let contributors = policy.select(&candidates);
+
+		match contributors[..] {
+			[] => Err(ConfigError::ValueNotFound(key)),
+			[index] => Ok(candidates[index].item().clone()),
+			_ => {
+				let mut item = StringItem::new(key);
+				for index in contributors {
+					for value in candidates[index].item().values() {
+						item.push(value.clone());
+					}
+				}
+
+				Ok(item)
+			}
+		}
+	}
+
+	/// Returns the items of all configuration sources that know about the key.
+	fn candidates(&self, key: &ConfPath) -> Vec<Candidate> {
+		self.sources.iter().enumerate().filter_map(|(index, source)| source.get(key.clone()).map(|item| Candidate::new(index, item))).collect()
+	}
+
+	/// Set the merge policy for a key or all keys below a prefix.
+	///
+	/// The merge policy defines how the values of multiple configuration
+	/// sources are combined. If multiple merge policies match a key the one
+	/// with the longest prefix is used. Setting a merge policy for a prefix
+	/// that already has one replaces the previous policy. For details see the
+	/// [`merge`](mod@crate::merge) module.
+	pub fn set_merge_policy(&mut self, prefix: ConfPath, policy: MergePolicy) {
+		if let Some(entry) = self.merge_policies.iter_mut().find(|(p, _)| *p == prefix) {
+			entry.1 = policy;
+		} else {
+			self.merge_policies.push((prefix, policy));
+		}
+	}
+
+	/// Register a deprecated alias for a configuration key.
+	///
+	/// If `new_key` is requested by calling [`get`](Self::get) and it is not
+	/// set, the value of `old_key` is returned instead and a [`Deprecation`]
+	/// warning is recorded. If two of the keys are set, `get` returns a
+	/// [`ConfigError::AliasConflict`] error. Multiple aliases can be registered
+	/// for the same key. For details see the [`alias`](mod@crate::alias) module.
+	pub fn add_alias(&mut self, old_key: ConfPath, new_key: ConfPath) {
+		self.aliases.push((old_key, new_key));
+	}
+
+	/// Returns the deprecation warnings recorded by [`get`](Self::get).
+	///
+	/// A warning is recorded the first time the value of a deprecated alias is
+	/// used. See [`add_alias`](Self::add_alias).
+	pub fn deprecations(&self) -> Vec<Deprecation> {
+		self.deprecations.lock().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+
+	/// Set the active profiles.
+	///
+	/// The profiles are checked in the order they are passed. The first
+	/// profile setting a key supplies its value. Passing an empty list
+	/// deactivates all profiles. For details see the
+	/// [`profile`](mod@crate::profile) module.
+	pub fn set_profiles<S: AsRef<str>>(&mut self, profiles: &[S]) {
+		self.profiles = profiles.iter().map(|profile| String::from(profile.as_ref())).collect();
+	}
+
+	/// Set the active profiles from a comma separated list stored within an
+	/// environment variable.
+	///
+	/// If the environment variable is not set or is not valid unicode, all
+	/// profiles are deactivated. See [`set_profiles`](Self::set_profiles).
+	pub fn set_profiles_from_env(&mut self, variable: &str) {
+		self.profiles = std::env::var(variable).map(|list| profile::split_list(&list)).unwrap_or_default();
+	}
+
+	/// Returns the active profiles.
+	pub fn profiles(&self) -> &[String] {
+		&self.profiles
+	}
+
+	/// Returns the key within every active profile together with the name of
+	/// the profile. Keys already located within a profile are not resolved
+	/// again.
+	fn profile_keys(&self, key: &ConfPath) -> Vec<(&str, ConfPath)> {
+		if self.profiles.is_empty() {
+			return Vec::default();
+		}
+
+		// Use a separate tree to not add the profile paths to the tree returned
+		// by `root`.
+		let profile_root = ConfPath::from(&[profile::PROFILE_PREFIX]);
+
+		if key.starts_with(&profile_root) {
+			return Vec::default();
+		}
+
+		self.profiles.iter().map(|profile| (profile.as_str(), profile_root.push(profile).join(key))).collect()
+	}
+
+	/// Returns the merge policy with the longest prefix matching the key.
+	fn merge_policy(&self, key: &ConfPath) -> MergePolicy {
+		self.merge_policies.iter()
+			.filter(|(prefix, _)| key.starts_with(prefix))
+			.max_by_key(|(prefix, _)| prefix.iter().count())
+			.map_or_else(MergePolicy::default, |(_, policy)| *policy)
+	}
+
+	/// Explain where the value of a configuration item is coming from.
+	///
+	/// Queries every registered configuration source for the passed key and
+	/// returns an [`Explanation`] listing all sources that know about the key
+	/// in the order they are queried by [`get`](Self::get). This is a
+	/// debugging aid. For details see the [`explain`](mod@crate::explain) module.
+	///
+	/// If an active profile supplies the value, the sources that know about the
+	/// key within this profile are listed and the name of the profile is
+	/// reported.
+	pub fn explain(&self, key: ConfPath) -> Explanation {
+		let policy = self.merge_policy(&key);
+
+		for (profile, profile_key) in self.profile_keys(&key) {
+			let candidates = self.candidates(&profile_key);
+			if !policy.select(&candidates).is_empty() {
+				return Explanation::new(key, Some(String::from(profile)), policy, candidates);
+			}
+		}
+
+		let candidates = self.candidates(&key);
+		Explanation::new(key, None, policy, candidates)
+	}
+
+	/// Enumerate all configuration keys known to the registered sources.
+	///
+	/// Returns the union of the keys returned by the
+	/// [`keys`](Source::keys) method of all configuration sources. Every key is
+	/// only returned once. The keys are sorted by their path components.
+	///
+	/// ## Example
+	///
+	/// ```rust
+	/// # use justconfig::Config;
+	/// # use justconfig::ConfPath;
+	/// # use justconfig::sources::defaults::Defaults;
+	/// #
+	/// let mut conf = Config::default();
+	///
+	/// let mut source_1 = Defaults::default();
+	/// source_1.set(conf.root().push_all(&["b"]), "source_1", "source 1");
+	/// conf.add_source(source_1);
+	///
+	/// let mut source_2 = Defaults::default();
+	/// source_2.set(conf.root().push_all(&["a", "c"]), "source_2", "source 2");
+	/// source_2.set(conf.root().push_all(&["b"]), "source_2", "source 2");
+	/// conf.add_source(source_2);
+	///
+	/// assert_eq!(conf.keys(), [ConfPath::from(&["a", "c"]), ConfPath::from(&["b"])]);
+	/// ```
+	pub fn keys(&self) -> Vec<ConfPath> {
+		let mut keys: Vec<(Vec<String>, ConfPath)> = self.sources.iter()
+			.flat_map(|source| source.keys())
+			.map(|key| (key.component_names(), key))
+			.collect();
+
+		keys.sort_by(|(a, _), (b, _)| a.cmp(b));
+		keys.dedup_by(|(a, _), (b, _)| a == b);
+
+		keys.into_iter().map(|(_, key)| key).collect()
+	}
+
+	/// Enumerate all configuration keys below the passed prefix.
+	///
+	/// Works like [`keys`](Self::keys) but only returns keys starting with
+	/// `prefix`. If the prefix itself is a configuration key it is returned as
+	/// well.
+	///
+	/// ## Example
+	///
+	/// ```rust
+	/// # use justconfig::Config;
+	/// # use justconfig::ConfPath;
+	/// # use justconfig::sources::defaults::Defaults;
+	/// #
+	/// let mut conf = Config::default();
+	///
+	/// let mut defaults = Defaults::default();
+	/// defaults.set(conf.root().push_all(&["db", "host"]), "localhost", "default");
+	/// defaults.set(conf.root().push_all(&["db", "port"]), "5432", "default");
+	/// defaults.set(conf.root().push_all(&["http", "port"]), "80", "default");
+	/// conf.add_source(defaults);
+	///
+	/// assert_eq!(conf.keys_under(&conf.root().push("db")), [ConfPath::from(&["db", "host"]), ConfPath::from(&["db", "port"])]);
+	/// ```
+	pub fn keys_under(&self, prefix: &ConfPath) -> Vec<ConfPath> {
+		self.keys().into_iter().filter(|key| key.starts_with(prefix)).collect()
+	}
+
+	/// Returns all configuration keys that were never requested.
+	///
+	/// Every key known to the configuration sources (see [`keys`](Self::keys))
+	/// that was never passed to [`get`](Self::get) is returned. This method
+	/// should be called after the application read its configuration. For
+	/// details see the [`unused`](mod@crate::unused) module.
+	pub fn unused_keys(&self) -> Vec<UnusedKey> {
+		let used_keys = self.used_keys.lock().unwrap_or_else(PoisonError::into_inner);
+
+		let mut used_key_list: Vec<ConfPath> = used_keys.iter().cloned().collect();
+		used_key_list.sort_by_cached_key(|key| key.to_string());
+
+		// Keys within a profile are used if the key without the profile prefix is used.
+		let is_used = |key: &ConfPath| used_keys.contains(key) || profile::strip_profile(key).is_some_and(|key| used_keys.contains(&key));
+
+		self.keys().into_iter().filter(|key| !is_used(key)).map(|key| {
+			// The key itself is reported. Active profiles must not redirect it.
+			let locations = self.candidates(&key).iter().flat_map(|candidate| candidate.item().values().iter().map(|value| value.source())).collect();
+
+			UnusedKey::new(key, locations, &used_key_list)
+		}).collect()
+	}
+
+	/// Reload all configuration sources.
+	///
+	/// Calls the [`reload`](Source::reload) method of every configuration
+	/// source. If a source reloaded its configuration information, the merged
+	/// configuration is compared with the configuration before reloading. The
+	/// detected changes are passed to all listeners registered by calling
+	/// [`on_change`](Self::on_change) and are returned by this method.
+	///
+	/// If reloading a source fails, the remaining sources are reloaded anyway.
+	/// The listeners are notified about the changes of the successfully
+	/// reloaded sources and the first error is returned. For details see the
+	/// [`reload`](mod@crate::reload) module.
+	pub fn reload(&self) -> Result<Vec<Change>, ConfigError> {
+		let old_snapshot = self.snapshot();
+
+		let mut reloaded = false;
+		let mut first_error = None;
+		for source in self.sources.iter() {
+			match source.reload() {
+				Ok(source_reloaded) => reloaded |= source_reloaded,
+				Err(error) => { first_error.get_or_insert(error); }
+			}
+		}
+
+		let changes = if reloaded { Change::diff(old_snapshot, self.snapshot()) } else { Vec::default() };
+
+		if !changes.is_empty() {
+			for listener in self.change_listeners.iter() {
+				listener(&changes);
+			}
+		}
+
+		match first_error {
+			Some(error) => Err(ConfigError::ReloadFailed(error)),
+			None => Ok(changes)
+		}
+	}
+
+	/// Register a listener that is notified about configuration changes.
+	///
+	/// The listener is called by [`reload`](Self::reload) with the list of
+	/// changed configuration items. It is only called if there are changes.
+	pub fn on_change(&mut self, listener: impl Fn(&[Change]) + Send + Sync + 'static) {
+		self.change_listeners.push(Box::new(listener));
+	}
+
+	/// Returns all configuration items with values sorted by their keys.
+	fn snapshot(&self) -> Vec<(Vec<String>, StringItem)> {
+		self.keys().into_iter()
+			.filter_map(|key| self.resolve(key).ok())
+			.filter(|item| !item.values().is_empty())
+			.map(|item| (item.key().component_names(), item))
+			.collect()
+	}
+
+	/// Strict mode check for unused configuration keys.
+	///
+	/// Returns a [`ConfigError::UnusedKeys`] error if there are configuration
+	/// keys that were never requested. See [`unused_keys`](Self::unused_keys).
+	pub fn check_unused(&self) -> Result<(), ConfigError> {
+		let unused_keys = self.unused_keys();
+
+		if unused_keys.is_empty() {
+			Ok(())
+		} else {
+			Err(ConfigError::UnusedKeys(unused_keys))
+		}
 	}
 }
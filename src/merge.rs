@@ -0,0 +1,207 @@
+//! Merge policies for combining the values of multiple configuration sources.
+//!
+//! By default [`get`](crate::Config::get) returns the values of the first
+//! configuration source that knows about a key. All other sources are ignored.
+//! This is fine for most configuration values but makes it impossible to
+//! combine lists. If a distribution supplies a list of plugins in
+//! `/usr/share` and the administrator wants to add a plugin in `/etc`, the
+//! administrator has to repeat the whole list.
+//!
+//! Merge policies allow changing this behaviour for a single key or all keys
+//! below a prefix. A merge policy is set by calling
+//! [`set_merge_policy`](crate::Config::set_merge_policy). If multiple policies
+//! match a key the one with the longest prefix is used.
+//!
+//! The merge policies use the order of the configuration sources as their
+//! priority. The first source added via
+//! [`add_source`](crate::Config::add_source) has the highest priority. If a
+//! configuration source returns an item without values, all sources with a
+//! lower priority are hidden. This way a configuration source can clear the
+//! values supplied by lower priority sources for every merge policy.
+//!
+//! Every value keeps its [`SourceLocation`](crate::item::SourceLocation).
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::ConfPath;
+//! # use justconfig::item::ValueExtractor;
+//! # use justconfig::merge::MergePolicy;
+//! # use justconfig::sources::defaults::Defaults;
+//! #
+//! let mut conf = Config::default();
+//! conf.set_merge_policy(conf.root().push("plugins"), MergePolicy::Append);
+//!
+//! let mut distribution = Defaults::default();
+//! distribution.put(conf.root().push("plugins"), "a", "distribution");
+//! distribution.put(conf.root().push("plugins"), "b", "distribution");
+//! conf.add_source(distribution);
+//!
+//! let mut admin = Defaults::default();
+//! admin.put(conf.root().push("plugins"), "c", "admin");
+//! conf.add_source(admin);
+//!
+//! let plugins: Vec<String> = conf.get(conf.root().push("plugins")).values(..).unwrap();
+//! assert_eq!(plugins, ["a", "b", "c"]);
+//! ```
+use crate::explain::Candidate;
+use std::fmt;
+
+/// Defines how the values of multiple configuration sources are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+	/// Use the values of the configuration source with the highest priority.
+	/// This is the default.
+	#[default]
+	FirstWins,
+	/// Use the values of the configuration source with the lowest priority.
+	LastWins,
+	/// Concatenate the values of all configuration sources. The values of the
+	/// source with the highest priority come first.
+	Append,
+	/// Concatenate the values of all configuration sources. The values of the
+	/// source with the lowest priority come first.
+	Prepend
+}
+
+impl fmt::Display for MergePolicy {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::FirstWins => write!(f, "first wins"),
+			Self::LastWins => write!(f, "last wins"),
+			Self::Append => write!(f, "append"),
+			Self::Prepend => write!(f, "prepend")
+		}
+	}
+}
+
+impl MergePolicy {
+	/// Selects the candidates that contribute values.
+	///
+	/// The candidates must be passed in priority order. Returns the indices of
+	/// the contributing candidates in the order their values must be
+	/// concatenated.
+	pub(crate) fn select(&self, candidates: &[Candidate]) -> Vec<usize> {
+		// A candidate without values hides all candidates with a lower priority.
+		let visible = candidates.iter().position(|candidate| candidate.item().values().is_empty()).map_or(candidates.len(), |pos| pos + 1);
+
+		match self {
+			Self::FirstWins => (0..visible).take(1).collect(),
+			Self::LastWins => (0..visible).rev().take(1).collect(),
+			Self::Append => (0..visible).collect(),
+			Self::Prepend => (0..visible).rev().collect()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::ConfPath;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use crate::sources::defaults::Defaults;
+
+	fn prepare_test_config() -> Config {
+		let mut c = Config::default();
+
+		let mut first = Defaults::default();
+		first.set(c.root().push_all(["list", "a"]), "a1", "first");
+		first.set(c.root().push_all(["list", "b"]), "b1", "first");
+		first.set(c.root().push_all(["list", "c"]), "c1", "first");
+		c.add_source(first);
+
+		let mut second = Defaults::default();
+		second.set(c.root().push_all(["list", "a"]), "a2.1", "second");
+		second.put(c.root().push_all(["list", "a"]), "a2.2", "second");
+		second.empty(c.root().push_all(["list", "b"]));
+		second.set(c.root().push_all(["list", "d"]), "d2", "second");
+		c.add_source(second);
+
+		let mut third = Defaults::default();
+		third.set(c.root().push_all(["list", "a"]), "a3", "third");
+		third.set(c.root().push_all(["list", "b"]), "b3", "third");
+		c.add_source(third);
+
+		c
+	}
+
+	fn values(c: &Config, key: &[&str]) -> Vec<String> {
+		c.get(ConfPath::from(key)).values(..).unwrap()
+	}
+
+	#[test]
+	fn first_wins() {
+		let c = prepare_test_config();
+
+		assert_eq!(values(&c, &["list", "a"]), ["a1"]);
+		assert_eq!(values(&c, &["list", "b"]), ["b1"]);
+	}
+
+	#[test]
+	fn last_wins() {
+		let mut c = prepare_test_config();
+		c.set_merge_policy(c.root().push("list"), MergePolicy::LastWins);
+
+		assert_eq!(values(&c, &["list", "a"]), ["a3"]);
+		assert_eq!(values(&c, &["list", "b"]), Vec::<String>::default());
+		assert_eq!(values(&c, &["list", "c"]), ["c1"]);
+	}
+
+	#[test]
+	fn append() {
+		let mut c = prepare_test_config();
+		c.set_merge_policy(c.root().push("list"), MergePolicy::Append);
+
+		assert_eq!(values(&c, &["list", "a"]), ["a1", "a2.1", "a2.2", "a3"]);
+		assert_eq!(values(&c, &["list", "b"]), ["b1"]);
+		assert_eq!(values(&c, &["list", "d"]), ["d2"]);
+	}
+
+	#[test]
+	fn prepend() {
+		let mut c = prepare_test_config();
+		c.set_merge_policy(c.root().push("list"), MergePolicy::Prepend);
+
+		assert_eq!(values(&c, &["list", "a"]), ["a3", "a2.1", "a2.2", "a1"]);
+		assert_eq!(values(&c, &["list", "b"]), ["b1"]);
+	}
+
+	#[test]
+	fn longest_prefix() {
+		let mut c = prepare_test_config();
+		c.set_merge_policy(c.root(), MergePolicy::Append);
+		c.set_merge_policy(c.root().push_all(["list", "a"]), MergePolicy::LastWins);
+
+		assert_eq!(values(&c, &["list", "a"]), ["a3"]);
+		assert_eq!(values(&c, &["list", "b"]), ["b1"]);
+
+		// Setting a policy for the same prefix replaces the previous one
+		c.set_merge_policy(c.root().push_all(["list", "a"]), MergePolicy::Prepend);
+		assert_eq!(values(&c, &["list", "a"]), ["a3", "a2.1", "a2.2", "a1"]);
+	}
+
+	#[test]
+	fn source_locations() {
+		let mut c = prepare_test_config();
+		c.set_merge_policy(c.root().push("list"), MergePolicy::Append);
+
+		let error = (c.get(ConfPath::from(&["list", "a"])).value() as Result<String, ConfigError>).unwrap_err();
+		assert_eq!(format!("{}", error), "More than 1 value found for key list.a@['default from first', 'default from second', 'default from second', 'default from third']");
+	}
+
+	#[test]
+	fn explain() {
+		let mut c = prepare_test_config();
+		c.set_merge_policy(c.root().push("list"), MergePolicy::Prepend);
+
+		let explanation = c.explain(ConfPath::from(&["list", "a"]));
+		assert_eq!(explanation.policy(), MergePolicy::Prepend);
+		assert_eq!(explanation.contributors().map(|c| c.source_index()).collect::<Vec<_>>(), [2, 1, 0]);
+		assert_eq!(explanation.shadowed().count(), 0);
+
+		let explanation = c.explain(ConfPath::from(&["list", "b"]));
+		assert_eq!(explanation.contributors().map(|c| c.source_index()).collect::<Vec<_>>(), [1, 0]);
+		assert_eq!(explanation.shadowed().map(|c| c.source_index()).collect::<Vec<_>>(), [2]);
+	}
+}
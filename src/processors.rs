@@ -374,7 +374,7 @@ impl Unquote for Result<StringItem, ConfigError> {
 }
 
 /// Type definition of a resolver function used by processors.
-type Resolver<'f> = &'f dyn Fn(&str) -> Result<String, Box<dyn Error>>;
+type Resolver<'f> = &'f dyn Fn(&str) -> Result<String, Box<dyn Error + Send + Sync>>;
 
 /// Expands an input string by calling a resolver function for each placeholder.
 ///
@@ -397,7 +397,7 @@ type Resolver<'f> = &'f dyn Fn(&str) -> Result<String, Box<dyn Error>>;
 /// ## Example
 /// If `enabler` is `$` and `start` is `{` the sequence `$${` will output `${`.
 /// The sequence `$$a` will output `$$a`.
-fn expand(input: &str, enabler: char, start: char, end: char, resolver: Resolver) -> Result<String, Box<dyn Error>> {
+fn expand(input: &str, enabler: char, start: char, end: char, resolver: Resolver) -> Result<String, Box<dyn Error + Send + Sync>> {
 	enum EnvState { Text, ProtoPlaceholder((usize, usize)), InPlaceholder((usize, usize)), Escaped }
 
 	let mut result = String::with_capacity(input.len());
@@ -577,7 +577,7 @@ impl Subst for Result<StringItem, ConfigError> {
 	///
 	/// assert!(result.is_err());
 	/// ```
-	fn expand(self, start: char, end: char, resolver: &dyn Fn(&str) -> Result<String, Box<dyn Error>>) -> Result<StringItem, ConfigError> {
+	fn expand(self, start: char, end: char, resolver: &dyn Fn(&str) -> Result<String, Box<dyn Error + Send + Sync>>) -> Result<StringItem, ConfigError> {
 		assert_ne!(start, '$');
 		assert_ne!(end, '$');
 
@@ -0,0 +1,172 @@
+//! Profiles selected at runtime.
+//!
+//! Configuration files often contain settings for different environments
+//! like development, staging and production. Instead of choosing between
+//! them by hand, the settings of every environment can be stored within a
+//! profile. The settings of the profile `dev` are stored below `profile.dev`.
+//!
+//! ```conf
+//! port=80
+//!
+//! [profile.dev]
+//! port=8080
+//! ```
+//!
+//! The active profiles are set by calling
+//! [`set_profiles`](crate::Config::set_profiles) or
+//! [`set_profiles_from_env`](crate::Config::set_profiles_from_env). For
+//! every requested key [`get`](crate::Config::get) first checks
+//! `profile.<name>.key` for every active profile in the order the profiles
+//! were passed. If no active profile sets the key, `key` itself is used.
+//! Because the profiles are resolved by the configuration system, they work
+//! with every type of configuration source.
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::item::ValueExtractor;
+//! # use justconfig::sources::text::ConfigText;
+//! #
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new("port=80\nhost=localhost\n[profile.dev]\nport=8080\n".as_bytes(), "myconfig.conf").unwrap());
+//! conf.set_profiles(&["dev"]);
+//!
+//! let port: u16 = conf.get(conf.root().push("port")).value().unwrap();
+//! let host: String = conf.get(conf.root().push("host")).value().unwrap();
+//! assert_eq!(port, 8080);
+//! assert_eq!(host, "localhost");
+//!
+//! let explanation = conf.explain(conf.root().push("port"));
+//! assert_eq!(explanation.profile(), Some("dev"));
+//! ```
+//!
+//! [`explain`](crate::Config::explain) reports the profile that supplied the
+//! value. Keys within a profile are not reported by
+//! [`unused_keys`](crate::Config::unused_keys) if the key without the profile
+//! prefix was requested. This is true for inactive profiles, too.
+use crate::confpath::ConfPath;
+
+/// Name of the configuration path component containing the profiles.
+pub const PROFILE_PREFIX: &str = "profile";
+
+/// Splits a comma separated list of profile names.
+///
+/// White-space around the names is removed and empty names are skipped.
+pub(crate) fn split_list(list: &str) -> Vec<String> {
+	list.split(',').map(str::trim).filter(|name| !name.is_empty()).map(String::from).collect()
+}
+
+/// Removes the profile prefix (`profile.<name>`) from a key.
+///
+/// Returns `None` if the key is not located within a profile.
+pub(crate) fn strip_profile(key: &ConfPath) -> Option<ConfPath> {
+	let rest = key.strip_prefix(&ConfPath::from(&[PROFILE_PREFIX]))?;
+	let profile = rest.iter().next()?;
+
+	rest.strip_prefix(&profile).filter(|key| !key.is_root())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use crate::sources::text::ConfigText;
+	use crate::sources::defaults::Defaults;
+	use crate::merge::MergePolicy;
+
+	fn prepare_test_config() -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigText::new("a=base\nb=base\nc=base\n[profile.dev]\na=dev\nb=dev\n[profile.local]\na=local\n".as_bytes(), "myconfig.conf").unwrap());
+
+		let mut defaults = Defaults::default();
+		defaults.set(c.root().push_all(["profile", "prod", "c"]), "prod", "prod default");
+		c.add_source(defaults);
+
+		c
+	}
+
+	fn value(c: &Config, key: &str) -> String {
+		(c.get(ConfPath::from(&[key])).value() as Result<String, ConfigError>).unwrap()
+	}
+
+	#[test]
+	fn priority() {
+		let mut c = prepare_test_config();
+
+		assert_eq!(value(&c, "a"), "base");
+
+		c.set_profiles(&["local", "dev"]);
+		assert_eq!(c.profiles(), ["local", "dev"]);
+		assert_eq!(value(&c, "a"), "local");
+		assert_eq!(value(&c, "b"), "dev");
+		assert_eq!(value(&c, "c"), "base");
+		assert_eq!(c.get(ConfPath::from(&["a"])).unwrap().key(), &ConfPath::from(&["a"]));
+
+		// Profiles are independent of the source type.
+		c.set_profiles(&["prod"]);
+		assert_eq!(value(&c, "c"), "prod");
+
+		// Keys within a profile are not resolved again.
+		assert_eq!((c.get(ConfPath::from(&["profile", "dev", "a"])).value() as Result<String, ConfigError>).unwrap(), "dev");
+	}
+
+	#[test]
+	fn from_env() {
+		let mut c = prepare_test_config();
+
+		std::env::set_var("JUSTCONFIG_TEST_PROFILES", " dev, ,local ");
+		c.set_profiles_from_env("JUSTCONFIG_TEST_PROFILES");
+		assert_eq!(c.profiles(), ["dev", "local"]);
+		assert_eq!(value(&c, "a"), "dev");
+
+		c.set_profiles_from_env("JUSTCONFIG_TEST_PROFILES_UNSET");
+		assert!(c.profiles().is_empty());
+	}
+
+	#[test]
+	fn explain() {
+		let mut c = prepare_test_config();
+		c.set_profiles(&["dev"]);
+
+		let explanation = c.explain(ConfPath::from(&["a"]));
+		assert_eq!(explanation.profile(), Some("dev"));
+		assert_eq!(explanation.to_string(), "Config key 'a' (profile 'dev'):\n  source #0 (used):\n    'dev'@'conf:myconfig.conf:5'");
+
+		assert_eq!(c.explain(ConfPath::from(&["c"])).profile(), None);
+
+		c.set_merge_policy(ConfPath::from(&["a"]), MergePolicy::Append);
+		assert!(c.explain(ConfPath::from(&["a"])).to_string().starts_with("Config key 'a' (profile 'dev', merged by append):"));
+	}
+
+	#[test]
+	fn unused() {
+		let c = prepare_test_config();
+		let _ = c.get(ConfPath::from(&["a"]));
+		let _ = c.get(ConfPath::from(&["c"]));
+
+		assert_eq!(c.unused_keys().iter().map(|unused| unused.key().to_string()).collect::<Vec<_>>(), ["b", "profile.dev.b"]);
+	}
+
+	#[test]
+	fn unused_location() {
+		let mut c = Config::default();
+		c.add_source(ConfigText::new("a=1\nb=base\n[profile.dev]\nb=dev\n".as_bytes(), "myconfig.conf").unwrap());
+		c.set_profiles(&["dev"]);
+		let _ = c.get(ConfPath::from(&["a"]));
+
+		let unused = c.unused_keys();
+		assert_eq!(unused.len(), 2);
+		assert_eq!(unused[0].key(), &ConfPath::from(&["b"]));
+		assert_eq!(unused[0].locations().iter().map(|location| location.to_string()).collect::<Vec<_>>(), ["conf:myconfig.conf:2"]);
+		assert_eq!(unused[1].key(), &ConfPath::from(&["profile", "dev", "b"]));
+		assert_eq!(unused[1].locations().iter().map(|location| location.to_string()).collect::<Vec<_>>(), ["conf:myconfig.conf:4"]);
+	}
+
+	#[test]
+	fn strip() {
+		assert_eq!(strip_profile(&ConfPath::from(&["profile", "dev", "a", "b"])).unwrap(), ConfPath::from(&["a", "b"]));
+		assert!(strip_profile(&ConfPath::from(&["profile", "dev"])).is_none());
+		assert!(strip_profile(&ConfPath::from(&["a", "b"])).is_none());
+	}
+}
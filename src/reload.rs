@@ -0,0 +1,161 @@
+//! Reloading configuration sources at runtime.
+//!
+//! Long running processes often need to pick up configuration changes without
+//! being restarted. Calling [`reload`](crate::Config::reload) asks every
+//! configuration source to reload its configuration information by calling the
+//! [`reload`](crate::source::Source::reload) method of the [`Source`](crate::source::Source)
+//! trait. Most sources do not support reloading and simply ignore this call.
+//!
+//! The [`Reloadable`](crate::sources::reloadable::Reloadable) source wraps
+//! other configuration sources and recreates them if the files they are read
+//! from change. Polling is used to detect changes. The application decides when
+//! to call [`reload`](crate::Config::reload).
+//!
+//! After reloading, the merged configuration is compared with the
+//! configuration before the reload. Every key that was added, removed or
+//! modified is reported as a [`Change`]. The changes are returned by
+//! [`reload`](crate::Config::reload) and passed to every listener registered
+//! via [`on_change`](crate::Config::on_change).
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::reload::Change;
+//! # use justconfig::sources::reloadable::Reloadable;
+//! # use std::path::Path;
+//! #
+//! # let config_file = std::env::temp_dir().join(format!("justconfig-reload-doc-{}.conf", std::process::id()));
+//! # std::fs::write(&config_file, "key=value\n").unwrap();
+//! #
+//! let mut conf = Config::default();
+//!
+//! conf.add_source(Reloadable::text_file(&config_file).unwrap());
+//! conf.on_change(|changes| {
+//!   for change in changes {
+//!     println!("{}", change);
+//!   }
+//! });
+//!
+//! // Call this periodically to pick up changes.
+//! let changes = conf.reload().unwrap();
+//! # assert!(changes.is_empty());
+//! # std::fs::remove_file(&config_file).unwrap();
+//! ```
+use crate::confpath::ConfPath;
+use crate::item::StringItem;
+use std::fmt;
+
+/// A change of a configuration item detected by
+/// [`reload`](crate::Config::reload).
+///
+/// The items carry the values and their source locations before and after
+/// reloading. Configuration items without values are treated like missing
+/// items.
+#[derive(Clone)]
+pub enum Change {
+	/// The configuration item did not have values before reloading.
+	Added(StringItem),
+	/// The configuration item does not have values after reloading.
+	Removed(StringItem),
+	/// The values of the configuration item changed. The first item contains
+	/// the old values, the second one the new values.
+	Modified(StringItem, StringItem)
+}
+
+impl Change {
+	/// Returns the configuration path of the changed item.
+	pub fn key(&self) -> &ConfPath {
+		match self {
+			Self::Added(item) => item.key(),
+			Self::Removed(item) => item.key(),
+			Self::Modified(_, item) => item.key()
+		}
+	}
+
+	/// Returns the configuration item before reloading or `None` if the item
+	/// was added.
+	pub fn old_item(&self) -> Option<&StringItem> {
+		match self {
+			Self::Added(_) => None,
+			Self::Removed(item) => Some(item),
+			Self::Modified(item, _) => Some(item)
+		}
+	}
+
+	/// Returns the configuration item after reloading or `None` if the item
+	/// was removed.
+	pub fn new_item(&self) -> Option<&StringItem> {
+		match self {
+			Self::Added(item) => Some(item),
+			Self::Removed(_) => None,
+			Self::Modified(_, item) => Some(item)
+		}
+	}
+
+	/// Compares two snapshots of the merged configuration.
+	///
+	/// The snapshots must be sorted by their keys and must not contain items
+	/// without values.
+	pub(crate) fn diff(old: Vec<(Vec<String>, StringItem)>, new: Vec<(Vec<String>, StringItem)>) -> Vec<Self> {
+		let mut changes = Vec::default();
+
+		let mut old = old.into_iter().peekable();
+		let mut new = new.into_iter().peekable();
+
+		loop {
+			let order = match (old.peek(), new.peek()) {
+				(Some((old_key, _)), Some((new_key, _))) => old_key.cmp(new_key),
+				(Some(_), None) => std::cmp::Ordering::Less,
+				(None, Some(_)) => std::cmp::Ordering::Greater,
+				(None, None) => break
+			};
+
+			match order {
+				std::cmp::Ordering::Less => changes.push(Self::Removed(old.next().unwrap().1)),
+				std::cmp::Ordering::Greater => changes.push(Self::Added(new.next().unwrap().1)),
+				std::cmp::Ordering::Equal => {
+					let (_, old_item) = old.next().unwrap();
+					let (_, new_item) = new.next().unwrap();
+
+					if !old_item.values().iter().map(|v| v.value()).eq(new_item.values().iter().map(|v| v.value())) {
+						changes.push(Self::Modified(old_item, new_item));
+					}
+				}
+			}
+		}
+
+		changes
+	}
+}
+
+fn values_formater(f: &mut fmt::Formatter, item: &StringItem) -> fmt::Result {
+	write!(f, "[")?;
+	for (i, value) in item.values().iter().enumerate() {
+		if i > 0 {
+			write!(f, ", ")?;
+		}
+
+		write!(f, "'{}'", value.value())?;
+	}
+	write!(f, "]")
+}
+
+impl fmt::Display for Change {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Added(item) => {
+				write!(f, "Added config key '{}': ", item.key())?;
+				values_formater(f, item)
+			},
+			Self::Removed(item) => {
+				write!(f, "Removed config key '{}': ", item.key())?;
+				values_formater(f, item)
+			},
+			Self::Modified(old_item, new_item) => {
+				write!(f, "Modified config key '{}': ", new_item.key())?;
+				values_formater(f, old_item)?;
+				write!(f, " -> ")?;
+				values_formater(f, new_item)
+			}
+		}
+	}
+}
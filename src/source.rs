@@ -1,9 +1,16 @@
 //! Contains the Source trait that must be implemented by configuration sources.
 use crate::item::StringItem;
 use crate::confpath::ConfPath;
+use std::error::Error;
 
 /// Trait that must be implemented by configuration sources.
-pub trait Source {
+///
+/// Configuration sources must be `Send` and `Sync`. This allows a
+/// [`Config`](crate::Config) to be shared between threads. Sources that need
+/// to modify their state from within `&self` methods (for example to
+/// [`reload`](Source::reload) their configuration information) must use a
+/// `Mutex` or `RwLock` to do so.
+pub trait Source: Send + Sync {
 	/// Get a configuration option.
 	///
 	/// This method is called by the configuration framework to retrieve the
@@ -26,4 +33,35 @@ pub trait Source {
 	///
 	/// See [`item`](crate::item) for more Information.
 	fn get(&self, key: ConfPath) -> Option<StringItem>;
+
+	/// Enumerate the configuration keys known to this source.
+	///
+	/// This method is called by [`Config::keys`](crate::Config::keys) to list
+	/// all keys of the merged configuration. It must return the keys of all
+	/// configuration items this source would return `Some(StringItem)` for.
+	/// The keys can be returned in any order.
+	///
+	/// Implementing this method is optional. The default implementation
+	/// returns an empty vector. Sources that do not implement this method can
+	/// still supply values but their keys will not be enumerated.
+	fn keys(&self) -> Vec<ConfPath> {
+		Vec::default()
+	}
+
+	/// Reload the configuration information.
+	///
+	/// This method is called by [`Config::reload`](crate::Config::reload). The
+	/// source should check if its configuration information changed and
+	/// reload it. It must return `Ok(true)` if the configuration information
+	/// was reloaded and `Ok(false)` if nothing changed.
+	///
+	/// If reloading fails, the source must keep its current configuration
+	/// information and return the error.
+	///
+	/// Implementing this method is optional. The default implementation does
+	/// nothing and returns `Ok(false)`. See [`reload`](crate::reload) for more
+	/// information.
+	fn reload(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+		Ok(false)
+	}
 }
@@ -0,0 +1,287 @@
+//! Command line arguments source.
+//!
+//! This source maps command line arguments onto configuration paths. By being
+//! its own source it can be ordered before other sources to allow the user to
+//! override configuration settings on the command line. Every value remembers
+//! the argument it was read from. This way error messages point the user to
+//! the faulty argument.
+//!
+//! The arguments are passed as an iterator. The name of the program must not
+//! be part of the passed arguments.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::args::Args;
+//!
+//! // Use std::env::args_os().skip(1) to pass the real command line.
+//! let args = [ "--server.port=8080", "--host", "a", "--host", "b", "-o", "debug=true" ];
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(Args::new(args).unwrap());
+//!
+//! let port: u16 = conf.get(ConfPath::from(&["server", "port"])).value().unwrap();
+//! let hosts: Vec<String> = conf.get(ConfPath::from(&["host"])).values(..).unwrap();
+//! let debug: bool = conf.get(ConfPath::from(&["debug"])).value().unwrap();
+//!
+//! assert_eq!(port, 8080);
+//! assert_eq!(hosts, ["a", "b"]);
+//! assert!(debug);
+//! ```
+//!
+//! ## Syntax
+//!
+//! The following forms of arguments are supported:
+//!
+//! | Argument              | Description                                              |
+//! |-----------------------|----------------------------------------------------------|
+//! | `--some.key=value`    | Sets `some.key` to `value`.                              |
+//! | `--some.key value`    | Sets `some.key` to `value`.                              |
+//! | `--some.key`          | Sets `some.key` to `true` if it is not followed by a value. |
+//! | `-o some.key=value`   | Sets `some.key` to `value`.                              |
+//! | `-osome.key=value`    | Sets `some.key` to `value`.                              |
+//!
+//! The key is split at every dot (`.`) to form the components of the
+//! configuration path. An argument following `--some.key` is used as its value
+//! if it does not start with a dash (`-`). Negative numbers like `-5` are
+//! used as values, too.
+//!
+//! If a key is passed multiple times, all values are added to the
+//! configuration item in the order they were passed.
+//!
+//! Other arguments are rejected. Parse the arguments that are not meant for
+//! the configuration (like positional arguments) before passing the remaining
+//! ones to this source.
+//!
+//! The source location of a value contains the position of the argument and
+//! the argument itself. `arg:3 (--port)` is the third argument.
+use crate::source::Source;
+use crate::item::{SourceLocation, StringItem, Value};
+use crate::confpath::ConfPath;
+
+use std::ffi::OsString;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::sync::Arc;
+use std::fmt;
+
+/// Enumeration containing parse errors.
+///
+/// The last parameter of every variant contains the position of the argument.
+#[derive(Debug)]
+pub enum Error {
+	/// The argument is not valid unicode.
+	NotUnicode(usize),
+	/// The argument is not an option setting a configuration value. The first
+	/// parameter contains the argument.
+	UnexpectedArgument(String, usize),
+	/// The option `-o` is not followed by `key=value`. The first parameter
+	/// contains the argument.
+	MissingValue(String, usize),
+	/// The key of the option is empty or contains empty path components. The
+	/// first parameter contains the argument.
+	InvalidKey(String, usize),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::NotUnicode(position) => write!(f, "Argument {} is not valid unicode", position),
+			Error::UnexpectedArgument(argument, position) => write!(f, "Unexpected argument '{}' at position {}", argument, position),
+			Error::MissingValue(argument, position) => write!(f, "Missing key=value for argument '{}' at position {}", argument, position),
+			Error::InvalidKey(argument, position) => write!(f, "Invalid key in argument '{}' at position {}", argument, position),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Source location for the Args configuration source.
+///
+/// This value is used to store the source of every configuration value for
+/// use in error messages.
+#[derive(Debug)]
+pub struct ArgSourceLocation {
+	position: usize,
+	argument: String
+}
+
+impl ArgSourceLocation {
+	fn new(position: usize, argument: &str) -> Arc<Self> {
+		Arc::new(Self {
+			position,
+			argument: argument.to_owned()
+		})
+	}
+}
+
+impl fmt::Display for ArgSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "arg:{} ({})", self.position, self.argument)
+	}
+}
+
+impl SourceLocation for ArgSourceLocation {}
+
+/// Implements the command line arguments source.
+pub struct Args {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl Args {
+	/// Parses the passed arguments into configuration information.
+	///
+	/// The method returns a new `Args` instance or an error if one of the
+	/// arguments could not be parsed.
+	///
+	/// See the [`args`](mod@super::args) module for more information.
+	pub fn new<I: IntoIterator>(args: I) -> Result<Box<Self>, Error> where I::Item: Into<OsString> {
+		Self::with_path(args, &ConfPath::default())
+	}
+
+	/// Parses the passed arguments and fills a `ConfPath` with the contained
+	/// keys.
+	///
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the arguments.
+	pub fn with_path<I: IntoIterator>(args: I, path_root: &ConfPath) -> Result<Box<Self>, Error> where I::Item: Into<OsString> {
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+
+		let mut args = (1usize..).zip(args.into_iter().map(Into::into)).peekable();
+		while let Some((position, arg)) = args.next() {
+			let arg = arg.into_string().map_err(|_| Error::NotUnicode(position))?;
+
+			if let Some(option) = arg.strip_prefix("--") {
+				let (key, value) = match option.split_once('=') {
+					Some((key, value)) => (key, String::from(value)),
+					None => (option, Self::next_value(&mut args)?.unwrap_or_else(|| String::from("true")))
+				};
+
+				conf.push(path_root, key, value, ArgSourceLocation::new(position, &format!("--{}", key)), &arg, position)?;
+			} else if let Some(option) = arg.strip_prefix("-o") {
+				let assignment = if option.is_empty() {
+					match args.next() {
+						Some((_, value)) => value.into_string().map_err(|_| Error::NotUnicode(position + 1))?,
+						None => return Err(Error::MissingValue(arg, position))
+					}
+				} else {
+					String::from(option)
+				};
+
+				match assignment.split_once('=') {
+					Some((key, value)) => conf.push(path_root, key, String::from(value), ArgSourceLocation::new(position, &format!("-o {}", key)), &arg, position)?,
+					None => return Err(Error::MissingValue(arg, position))
+				}
+			} else {
+				return Err(Error::UnexpectedArgument(arg, position));
+			}
+		}
+
+		Ok(Box::new(conf))
+	}
+
+	/// Returns the next argument if it is a value and not an option.
+	fn next_value(args: &mut Peekable<impl Iterator<Item=(usize, OsString)>>) -> Result<Option<String>, Error> {
+		let is_value = match args.peek() {
+			Some((_, value)) => match value.to_string_lossy().strip_prefix('-') {
+				Some(rest) => rest.starts_with(|c: char| c.is_ascii_digit()),
+				None => true
+			},
+			None => false
+		};
+
+		if is_value {
+			let (position, value) = args.next().unwrap();	// We peeked the value above.
+			Ok(Some(value.into_string().map_err(|_| Error::NotUnicode(position))?))
+		} else {
+			Ok(None)
+		}
+	}
+
+	fn push(&mut self, path_root: &ConfPath, key: &str, value: String, location: Arc<ArgSourceLocation>, arg: &str, position: usize) -> Result<(), Error> {
+		if key.split('.').any(str::is_empty) {
+			return Err(Error::InvalidKey(arg.to_owned(), position));
+		}
+
+		let path = path_root.push_all(key.split('.'));
+		self.items.entry(path.clone()).or_insert_with(|| StringItem::new(path)).push(Value::new(value, location));
+
+		Ok(())
+	}
+}
+
+impl Source for Args {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn prepare_test_config(args: &[&str]) -> Config {
+		let mut c = Config::default();
+		c.add_source(Args::new(args.iter().copied()).unwrap());
+
+		c
+	}
+
+	fn values(c: &Config, key: &[&str]) -> Vec<String> {
+		c.get(ConfPath::from(key)).values(..).unwrap()
+	}
+
+	#[test]
+	fn parsing() {
+		let c = prepare_test_config(&[
+			"--a.b=1", "--c", "2", "--flag", "--d", "-5", "--e=x=y", "--empty=",
+			"--multi", "1", "--multi=2", "-o", "f.g=3", "-oh=4", "--last"
+		]);
+
+		assert_eq!(values(&c, &["a", "b"]), ["1"]);
+		assert_eq!(values(&c, &["c"]), ["2"]);
+		assert_eq!(values(&c, &["flag"]), ["true"]);
+		assert_eq!(values(&c, &["d"]), ["-5"]);
+		assert_eq!(values(&c, &["e"]), ["x=y"]);
+		assert_eq!(values(&c, &["empty"]), [""]);
+		assert_eq!(values(&c, &["multi"]), ["1", "2"]);
+		assert_eq!(values(&c, &["f", "g"]), ["3"]);
+		assert_eq!(values(&c, &["h"]), ["4"]);
+		assert_eq!(values(&c, &["last"]), ["true"]);
+	}
+
+	#[test]
+	fn source_location() {
+		let c = prepare_test_config(&["--a=1", "--port", "x", "-o", "b=y"]);
+
+		let error = (c.get(ConfPath::from(&["port"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'arg:2 (--port)'");
+
+		let error = (c.get(ConfPath::from(&["b"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'arg:4 (-o b)'");
+	}
+
+	#[test]
+	fn errors() {
+		fn parse_error(args: &[&str]) -> String {
+			Args::new(args.iter().copied()).err().unwrap().to_string()
+		}
+
+		assert_eq!(parse_error(&["--a=1", "positional"]), "Unexpected argument 'positional' at position 2");
+		assert_eq!(parse_error(&["-x"]), "Unexpected argument '-x' at position 1");
+		assert_eq!(parse_error(&["-o"]), "Missing key=value for argument '-o' at position 1");
+		assert_eq!(parse_error(&["-o", "key"]), "Missing key=value for argument '-o' at position 1");
+		assert_eq!(parse_error(&["--a..b=1"]), "Invalid key in argument '--a..b=1' at position 1");
+		assert_eq!(parse_error(&["--=1"]), "Invalid key in argument '--=1' at position 1");
+	}
+}
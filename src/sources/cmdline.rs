@@ -0,0 +1,306 @@
+//! Kernel command line source.
+//!
+//! This source parses a string formatted like the Linux kernel command line
+//! (`/proc/cmdline`). Appliances often receive boot-time settings this way.
+//!
+//! Only parameters starting with a namespace followed by a dot are used. The
+//! namespace is removed and the remainder of the parameter name is split at
+//! the dots to form the configuration path. With the namespace `myapp` the
+//! parameter `myapp.log.level=debug` sets `log.level` to `debug`. All other
+//! parameters are ignored.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::cmdline::ConfigCmdline;
+//!
+//! let cmdline = r#"root=/dev/sda1 quiet myapp.log_level=debug myapp.debug myapp.motd="Hello World""#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigCmdline::new(cmdline, "myapp", "cmdline").unwrap());
+//!
+//! let log_level: String = conf.get(ConfPath::from(&["log_level"])).value().unwrap();
+//! let debug: bool = conf.get(ConfPath::from(&["debug"])).value().unwrap();
+//! let motd: String = conf.get(ConfPath::from(&["motd"])).value().unwrap();
+//!
+//! assert_eq!(log_level, "debug");
+//! assert!(debug);
+//! assert_eq!(motd, "Hello World");
+//! ```
+//!
+//! ## Format
+//!
+//! * Parameters are separated by white-space.
+//! * Double quotes group characters containing white-space. The quotes are
+//!   removed. There are no escape sequences.
+//! * `name=value` sets the configuration item to `value`. `name=` sets it to an
+//!   empty string.
+//! * A parameter without a value (a flag) sets the configuration item to
+//!   `true`.
+//! * If a parameter is passed multiple times, all values are added to the
+//!   configuration item in the order they were passed.
+//! * Parsing stops at `--`. The following parameters are passed to init by
+//!   the kernel. A quoted `"--"` does not stop parsing.
+//! * A missing closing quote is only reported as an error if the parameter
+//!   belongs to the namespace. Like the kernel, the parameter extends up to
+//!   the end of the command line in this case.
+//!
+//! The source location of every value contains the name of the source and the
+//! position of the parameter on the command line (`cmdline:/proc/cmdline:3`).
+use crate::source::Source;
+use crate::item::{SourceLocation, StringItem, Value};
+use crate::confpath::ConfPath;
+
+use std::path::Path;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::fmt;
+use std::fs;
+
+/// Path of the kernel command line of the running system.
+const PROC_CMDLINE: &str = "/proc/cmdline";
+
+/// Enumeration containing parse errors.
+#[derive(Debug)]
+pub enum Error {
+	/// The namespace passed to the constructor is empty.
+	EmptyNamespace,
+	/// A parameter is missing its closing quote.
+	UnterminatedQuote(Arc<CmdlineSourceLocation>),
+	/// The name of a parameter contains empty path components. The first
+	/// parameter contains the name.
+	InvalidKey(String, Arc<CmdlineSourceLocation>),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::EmptyNamespace => write!(f, "The namespace must not be empty"),
+			Error::UnterminatedQuote(location) => write!(f, "Missing closing quote in {}", location),
+			Error::InvalidKey(key, location) => write!(f, "Invalid parameter name '{}' in {}", key, location),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Source location for the ConfigCmdline configuration source.
+/// This value is used to store the source of every configuration value for
+/// use in error messages.
+#[derive(Debug)]
+pub struct CmdlineSourceLocation {
+	source_name: String,
+	position: usize
+}
+
+impl CmdlineSourceLocation {
+	fn new(source_name: &str, position: usize) -> Arc<Self> {
+		Arc::new(Self {
+			source_name: source_name.to_owned(),
+			position
+		})
+	}
+}
+
+impl fmt::Display for CmdlineSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "cmdline:{}:{}", self.source_name, self.position)
+	}
+}
+
+impl SourceLocation for CmdlineSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.source_name)
+	}
+}
+
+/// Implements the kernel command line source.
+pub struct ConfigCmdline {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigCmdline {
+	/// Parses a kernel command line into configuration information.
+	///
+	/// Only parameters starting with `namespace` followed by a dot are used.
+	/// As the third parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	pub fn new(cmdline: &str, namespace: &str, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(cmdline, namespace, source_name, &ConfPath::default())
+	}
+
+	/// Parses a kernel command line and fills a `ConfPath` with the contained
+	/// keys.
+	///
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the command line.
+	pub fn with_path(cmdline: &str, namespace: &str, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		if namespace.is_empty() {
+			return Err(Error::EmptyNamespace);
+		}
+
+		let prefix = format!("{}.", namespace);
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+
+		for (position, (raw, parameter, terminated)) in (1usize..).zip(Self::split(cmdline)) {
+			let location = CmdlineSourceLocation::new(source_name, position);
+
+			// A quoted "--" does not end the parameters.
+			if raw == "--" {
+				break;
+			}
+
+			let (name, value) = match parameter.split_once('=') {
+				Some((name, value)) => (name, value),
+				None => (parameter.as_str(), "true")
+			};
+
+			if let Some(key) = name.strip_prefix(&prefix) {
+				if !terminated {
+					return Err(Error::UnterminatedQuote(location));
+				}
+
+				if key.split('.').any(str::is_empty) {
+					return Err(Error::InvalidKey(name.to_owned(), location));
+				}
+
+				let path = path_root.push_all(key.split('.'));
+				conf.items.entry(path.clone()).or_insert_with(|| StringItem::new(path)).push(Value::new(value.to_owned(), location));
+			}
+		}
+
+		Ok(Box::new(conf))
+	}
+
+	/// Reads the kernel command line from a file and parses it.
+	///
+	/// The path of the file is used as the name of the configuration source.
+	pub fn from_file(path: impl AsRef<Path>, namespace: &str) -> Result<Box<Self>, Error> {
+		let cmdline = fs::read_to_string(path.as_ref())?;
+
+		Self::new(&cmdline, namespace, &path.as_ref().to_string_lossy())
+	}
+
+	/// Reads the kernel command line of the running system from
+	/// `/proc/cmdline` and parses it.
+	pub fn from_proc(namespace: &str) -> Result<Box<Self>, Error> {
+		Self::from_file(PROC_CMDLINE, namespace)
+	}
+
+	/// Splits the command line into parameters and removes the quotes.
+	/// Returns the raw parameter, the parameter without quotes and `false` if
+	/// the closing quote of the parameter is missing.
+	fn split(cmdline: &str) -> impl Iterator<Item=(&str, String, bool)> + '_ {
+		let mut chars = cmdline.char_indices().peekable();
+
+		std::iter::from_fn(move || {
+			while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+			let start = chars.peek()?.0;
+
+			let mut parameter = String::new();
+			let mut in_quotes = false;
+			while let Some((_, c)) = chars.next_if(|(_, c)| in_quotes || !c.is_whitespace()) {
+				if c == '"' {
+					in_quotes = !in_quotes;
+				} else {
+					parameter.push(c);
+				}
+			}
+
+			let end = chars.peek().map_or(cmdline.len(), |(pos, _)| *pos);
+			Some((&cmdline[start..end], parameter, !in_quotes))
+		})
+	}
+}
+
+impl Source for ConfigCmdline {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn values(c: &Config, key: &[&str]) -> Vec<String> {
+		c.get(ConfPath::from(key)).values(..).unwrap()
+	}
+
+	#[test]
+	fn fixture() {
+		let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("cmdline").join("cmdline");
+
+		let mut c = Config::default();
+		c.add_source(ConfigCmdline::from_file(&fixture, "myapp").unwrap());
+
+		assert_eq!(values(&c, &["log_level"]), ["debug"]);
+		assert_eq!(values(&c, &["debug"]), ["true"]);
+		assert_eq!(values(&c, &["empty"]), [""]);
+		assert_eq!(values(&c, &["motd"]), ["Hello World"]);
+		assert_eq!(values(&c, &["quoted"]), ["a b=c"]);
+		assert_eq!(values(&c, &["net", "iface"]), ["eth0", "eth1"]);
+		assert_eq!(c.keys().len(), 6);
+
+		let error = (c.get(ConfPath::from(&["log_level"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), format!("invalid digit found in string@'cmdline:{}:4'", fixture.display()));
+	}
+
+	#[test]
+	fn end_of_parameters() {
+		let c = ConfigCmdline::new("myapp.a=1 \"--\" myapp.b=2 -- myapp.c=3", "myapp", "cmdline").unwrap();
+
+		let mut keys = c.keys();
+		keys.sort_by_key(|k| k.to_string());
+		assert_eq!(keys, [ConfPath::from(&["a"]), ConfPath::from(&["b"])]);
+	}
+
+	#[test]
+	fn errors() {
+		assert!(matches!(ConfigCmdline::new("myapp.a=1", "", "cmdline"), Err(Error::EmptyNamespace)));
+
+		match ConfigCmdline::new("quiet myapp.a=\"open", "myapp", "cmdline") {
+			Err(Error::UnterminatedQuote(location)) => assert_eq!(location.to_string(), "cmdline:cmdline:2"),
+			_ => panic!("Missing closing quote not detected.")
+		}
+
+		// Parameters outside of the namespace are not checked.
+		let c = ConfigCmdline::new("myapp.a=1 foo=\"x", "myapp", "cmdline").unwrap();
+		assert_eq!(c.keys(), [ConfPath::from(&["a"])]);
+
+		match ConfigCmdline::new("myapp..a=1", "myapp", "cmdline") {
+			Err(Error::InvalidKey(key, location)) => {
+				assert_eq!(key, "myapp..a");
+				assert_eq!(location.to_string(), "cmdline:cmdline:1");
+			},
+			_ => panic!("Invalid key not detected.")
+		}
+	}
+}
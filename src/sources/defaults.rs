@@ -45,7 +45,7 @@
 use crate::source::Source;
 use crate::item::{SourceLocation, StringItem, Value};
 use crate::confpath::ConfPath;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -59,8 +59,8 @@ pub struct DefaultSourceLocation {
 }
 
 impl DefaultSourceLocation {
-	fn new(source: &str) -> Rc<Self> {
-		Rc::new(Self {
+	fn new(source: &str) -> Arc<Self> {
+		Arc::new(Self {
 			source: source.to_owned()
 		})
 	}
@@ -161,6 +161,10 @@ impl Source for Defaults {
 	fn get(&self, key: ConfPath) -> Option<StringItem> {
 		self.items.get(&key).cloned()
 	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
 }
 
 #[cfg(test)]
@@ -204,4 +208,19 @@ mod tests {
 		assert_eq!((c.get(ConfPath::from(&["testD"])).value() as Result<String, ConfigError>).unwrap(), "DdD");
 		assert_eq!((c.get(ConfPath::from(&["testE"])).value() as Result<String, ConfigError>).unwrap(), "EeE");
 	}
+
+	#[test]
+	fn keys() {
+		let mut d = Defaults::default();
+
+		d.set(ConfPath::from(&["testA"]), "AaA", "sourceA");
+		d.put(ConfPath::from(&["testB"]), "BbB", "sourceB.1");
+		d.put(ConfPath::from(&["testB"]), "bBb", "sourceB.2");
+		d.empty(ConfPath::from(&["testC"]));
+
+		let mut keys: Vec<String> = d.keys().iter().map(|k| k.to_string()).collect();
+		keys.sort();
+
+		assert_eq!(keys, ["testA", "testB", "testC"]);
+	}
 }
\ No newline at end of file
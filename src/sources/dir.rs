@@ -0,0 +1,347 @@
+//! Directory source.
+//!
+//! This source reads a directory containing one file per configuration value.
+//! This is the way Docker and Kubernetes supply secrets to containers
+//! (`/run/secrets/db_password`). The path of every file relative to the root
+//! directory is mapped onto a configuration path. The content of the file
+//! becomes the value of the configuration item.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::dir::ConfigDir;
+//! # let secrets = std::env::temp_dir().join(format!("justconfig-dir-doc-{}", std::process::id()));
+//! # std::fs::create_dir_all(secrets.join("db")).unwrap();
+//! # std::fs::write(secrets.join("db").join("password"), "secret\n").unwrap();
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigDir::new(&secrets).unwrap());
+//!
+//! // Read from the file db/password
+//! let password: String = conf.get(ConfPath::from(&["db", "password"])).value().unwrap();
+//! assert_eq!(password, "secret");
+//! # std::fs::remove_dir_all(&secrets).unwrap();
+//! ```
+//!
+//! ## Mapping
+//!
+//! * Every sub-directory adds a path component named like the directory.
+//! * Every file adds a path component named like the file. The content of the
+//!   file must be UTF-8 encoded. By default a trailing newline is removed from
+//!   the content.
+//! * Entries whose names start with a dot (`.`) are ignored. This skips the
+//!   `..data` symlink and the timestamped directories Kubernetes uses to update
+//!   projected volumes atomically.
+//! * Symbolic links are followed.
+//! * Files larger than the size limit are rejected. The default limit is 64 KiB.
+//!
+//! The behaviour can be changed by passing [`DirOptions`] to
+//! [`with_options`](ConfigDir::with_options).
+//!
+//! The source location of every value contains the path of the file it was
+//! read from (`file:/run/secrets/db_password`).
+use crate::source::Source;
+use crate::item::{SourceLocation, StringItem, Value};
+use crate::confpath::ConfPath;
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::sync::Arc;
+use std::fmt;
+
+/// Maximum nesting depth of directories.
+const MAX_DIR_DEPTH: usize = 16;
+
+/// Enumeration containing errors that occur while reading the directory.
+#[derive(Debug)]
+pub enum Error {
+	/// The name of the file or directory is not valid unicode.
+	NotUnicode(PathBuf),
+	/// The content of the file is not valid UTF-8.
+	InvalidContent(PathBuf),
+	/// The file is larger than the size limit. The second parameter contains the
+	/// size limit.
+	TooLarge(PathBuf, u64),
+	/// Directories are nested deeper than the maximum depth of 16 directories.
+	TooDeep(PathBuf),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::NotUnicode(path) => write!(f, "The name of '{}' is not valid unicode", path.display()),
+			Error::InvalidContent(path) => write!(f, "The content of '{}' is not valid UTF-8", path.display()),
+			Error::TooLarge(path, limit) => write!(f, "The file '{}' is larger than {} bytes", path.display(), limit),
+			Error::TooDeep(path) => write!(f, "Maximum directory depth exceeded while reading '{}'", path.display()),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Source location for configuration values read from a file.
+///
+/// This value is used to store the source of every configuration value for
+/// use in error messages.
+#[derive(Debug)]
+pub struct FileSourceLocation {
+	path: String
+}
+
+impl FileSourceLocation {
+	fn new(path: &Path) -> Arc<Self> {
+		Arc::new(Self {
+			path: path.to_string_lossy().into_owned()
+		})
+	}
+}
+
+impl fmt::Display for FileSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "file:{}", self.path)
+	}
+}
+
+impl SourceLocation for FileSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.path)
+	}
+}
+
+/// Options for reading the files of a directory.
+#[derive(Debug, Clone)]
+pub struct DirOptions {
+	/// Remove a trailing newline (`\n` or `\r\n`) from the content of every file.
+	/// Defaults to `true`.
+	pub trim_newline: bool,
+	/// Maximum size of a file in bytes. Defaults to 64 KiB.
+	pub size_limit: u64
+}
+
+impl Default for DirOptions {
+	fn default() -> Self {
+		Self {
+			trim_newline: true,
+			size_limit: 64 * 1024
+		}
+	}
+}
+
+impl DirOptions {
+	/// Reads the content of a file as a configuration value.
+	pub(crate) fn read_value(&self, path: &Path) -> Result<Arc<Value<String>>, Error> {
+		let mut content = Vec::new();
+		File::open(path)?.take(self.size_limit + 1).read_to_end(&mut content)?;
+
+		if content.len() as u64 > self.size_limit {
+			return Err(Error::TooLarge(path.to_owned(), self.size_limit));
+		}
+
+		let mut content = String::from_utf8(content).map_err(|_| Error::InvalidContent(path.to_owned()))?;
+		if self.trim_newline && content.ends_with('\n') {
+			content.pop();
+			if content.ends_with('\r') {
+				content.pop();
+			}
+		}
+
+		Ok(Value::new(content, FileSourceLocation::new(path)))
+	}
+}
+
+/// Implements the directory source.
+pub struct ConfigDir {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigDir {
+	/// Reads all files within the directory `root` by using the default
+	/// options.
+	///
+	/// The method returns a new `ConfigDir` instance or an error if the
+	/// directory could not be read.
+	pub fn new(root: impl AsRef<Path>) -> Result<Box<Self>, Error> {
+		Self::with_options(root, &ConfPath::default(), &DirOptions::default())
+	}
+
+	/// Reads all files within the directory `root` and fills a `ConfPath` with
+	/// the contained keys.
+	///
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while reading the directory.
+	pub fn with_path(root: impl AsRef<Path>, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		Self::with_options(root, path_root, &DirOptions::default())
+	}
+
+	/// Reads all files within the directory `root` by using the passed options.
+	///
+	/// ```rust
+	/// use justconfig::Config;
+	/// use justconfig::sources::dir::{ConfigDir, DirOptions};
+	/// # let secrets = std::env::temp_dir().join(format!("justconfig-dir-options-doc-{}", std::process::id()));
+	/// # std::fs::create_dir_all(&secrets).unwrap();
+	///
+	/// let options = DirOptions {
+	///   trim_newline: false,
+	///   ..DirOptions::default()
+	/// };
+	///
+	/// let mut conf = Config::default();
+	/// conf.add_source(ConfigDir::with_options(&secrets, &conf.root(), &options).unwrap());
+	/// # std::fs::remove_dir_all(&secrets).unwrap();
+	/// ```
+	pub fn with_options(root: impl AsRef<Path>, path_root: &ConfPath, options: &DirOptions) -> Result<Box<Self>, Error> {
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+
+		conf.read_dir(root.as_ref(), path_root, options, 0)?;
+
+		Ok(Box::new(conf))
+	}
+
+	fn read_dir(&mut self, dir: &Path, path: &ConfPath, options: &DirOptions, depth: usize) -> Result<(), Error> {
+		if depth >= MAX_DIR_DEPTH {
+			return Err(Error::TooDeep(dir.to_owned()));
+		}
+
+		for entry in fs::read_dir(dir)? {
+			let entry_path = entry?.path();
+			let name = entry_path.file_name().unwrap().to_str().ok_or_else(|| Error::NotUnicode(entry_path.clone()))?;	// Entries returned by read_dir always have a file name.
+
+			if name.starts_with('.') {
+				continue;
+			}
+
+			// Follow symbolic links
+			let metadata = fs::metadata(&entry_path)?;
+			let entry_key = path.push(name);
+
+			if metadata.is_dir() {
+				self.read_dir(&entry_path, &entry_key, options, depth + 1)?;
+			} else if metadata.is_file() {
+				let value = options.read_value(&entry_path)?;
+				self.items.insert(entry_key.clone(), StringItem::from(entry_key, &[value]));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Source for ConfigDir {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn temp_dir(name: &str) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("justconfig-dir-{}-{}", std::process::id(), name));
+		let _ = fs::remove_dir_all(&path);
+		fs::create_dir_all(&path).unwrap();
+
+		path
+	}
+
+	#[test]
+	fn read() {
+		let root = temp_dir("read");
+		fs::write(root.join("plain"), "value").unwrap();
+		fs::write(root.join("newline"), "value\r\n").unwrap();
+		fs::write(root.join("two_newlines"), "value\n\n").unwrap();
+		fs::write(root.join(".hidden"), "hidden").unwrap();
+		fs::create_dir_all(root.join("nested").join("dir")).unwrap();
+		fs::write(root.join("nested").join("dir").join("key"), "nested\n").unwrap();
+
+		let mut c = Config::default();
+		c.add_source(ConfigDir::new(&root).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["plain"])).value() as Result<String, ConfigError>).unwrap(), "value");
+		assert_eq!((c.get(ConfPath::from(&["newline"])).value() as Result<String, ConfigError>).unwrap(), "value");
+		assert_eq!((c.get(ConfPath::from(&["two_newlines"])).value() as Result<String, ConfigError>).unwrap(), "value\n");
+		assert_eq!((c.get(ConfPath::from(&["nested", "dir", "key"])).value() as Result<String, ConfigError>).unwrap(), "nested");
+		assert_eq!(c.keys().len(), 4);
+
+		let error = (c.get(ConfPath::from(&["plain"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), format!("invalid digit found in string@'file:{}'", root.join("plain").display()));
+
+		fs::remove_dir_all(&root).unwrap();
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn kubernetes_layout() {
+		use std::os::unix::fs::symlink;
+
+		let root = temp_dir("kubernetes");
+		fs::create_dir(root.join("..2024_01_01_00_00_00.1")).unwrap();
+		fs::write(root.join("..2024_01_01_00_00_00.1").join("db_password"), "secret\n").unwrap();
+		symlink("..2024_01_01_00_00_00.1", root.join("..data")).unwrap();
+		symlink(Path::new("..data").join("db_password"), root.join("db_password")).unwrap();
+
+		let mut c = Config::default();
+		c.add_source(ConfigDir::new(&root).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["db_password"])).value() as Result<String, ConfigError>).unwrap(), "secret");
+		assert_eq!(c.keys(), [ConfPath::from(&["db_password"])]);
+
+		fs::remove_dir_all(&root).unwrap();
+	}
+
+	#[test]
+	fn options() {
+		let root = temp_dir("options");
+		fs::write(root.join("key"), "value\n").unwrap();
+
+		let options = DirOptions {
+			trim_newline: false,
+			size_limit: 6
+		};
+
+		let mut c = Config::default();
+		c.add_source(ConfigDir::with_options(&root, &c.root(), &options).unwrap());
+		assert_eq!((c.get(ConfPath::from(&["key"])).value() as Result<String, ConfigError>).unwrap(), "value\n");
+
+		let options = DirOptions {
+			size_limit: 5,
+			..DirOptions::default()
+		};
+
+		match ConfigDir::with_options(&root, &c.root(), &options) {
+			Err(Error::TooLarge(path, 5)) => assert_eq!(path, root.join("key")),
+			_ => panic!("Size limit not enforced.")
+		}
+
+		fs::remove_dir_all(&root).unwrap();
+	}
+}
@@ -0,0 +1,497 @@
+//! Dotenv source.
+//!
+//! This source parses `.env` files into configuration information. These files
+//! are often used during development to supply the environment variables an
+//! application expects without setting them in the shell.
+//!
+//! Any struct that implements `Read` can be used as a source for configuration
+//! values. The file must be UTF-8 encoded.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::dotenv::ConfigDotenv;
+//!
+//! let dotenv = r#"
+//! ## Database settings
+//! export DB_HOST=localhost
+//! DB_PORT=5432
+//! DB_URL="postgres://${DB_HOST}:${DB_PORT}/app"
+//! DB_PASSWORD='pa$$word'
+//! "#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigDotenv::new(dotenv.as_bytes(), ".env").unwrap());
+//!
+//! let url: String = conf.get(conf.root().push("DB_URL")).value().unwrap();
+//! let password: String = conf.get(conf.root().push("DB_PASSWORD")).value().unwrap();
+//!
+//! assert_eq!(url, "postgres://localhost:5432/app");
+//! assert_eq!(password, "pa$$word");
+//! ```
+//!
+//! ## Format
+//!
+//! Every line contains an assignment of the form `KEY=VALUE`:
+//!
+//! * Empty lines and lines starting with `#` are ignored.
+//! * The assignment can be prefixed with `export`.
+//! * Unquoted values end at the first ` #`, which starts a comment. White-space
+//!   around unquoted values is removed.
+//! * Values in single quotes (`'...'`) are used literally.
+//! * Values in double quotes (`"..."`) support the escape sequences `\n`, `\r`,
+//!   `\t`, `\"`, `\\` and `\$`.
+//! * Quoted values can span multiple lines. After the closing quote only
+//!   white-space and a comment are allowed.
+//! * `${VAR}` and `$VAR` within unquoted and double quoted values are replaced
+//!   by the value of a key set earlier within the same file. If the file does
+//!   not contain the key, the environment variable with that name is used.
+//!   Like most dotenv implementations, a variable that does not exist is
+//!   replaced by an empty string. Values containing a literal dollar sign
+//!   must be single quoted or the dollar sign must be escaped (`\$`).
+//! * If a key is set multiple times the last value wins.
+//!
+//! Every key is stored as a single configuration path component. `DB_HOST` is
+//! stored as `DB_HOST`.
+//!
+//! The source locations of the values contain the name of the source and the
+//! lines the value was read from.
+use crate::source::Source;
+use crate::item::{SourceLocation, StringItem, Value};
+use crate::confpath::ConfPath;
+
+use std::io::Read;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+use std::fmt;
+use std::env;
+
+/// Enumeration containing parse errors.
+#[derive(Debug)]
+pub enum Error {
+	/// A line does not contain a key-value-delimiter (`=`).
+	MissingKeyValueDelimiter(Arc<DotenvSourceLocation>),
+	/// The key is empty or contains characters other than letters, digits,
+	/// underscores, dots and dashes. The first parameter contains the key.
+	InvalidKey(String, Arc<DotenvSourceLocation>),
+	/// A quoted value is missing its closing quote.
+	UnterminatedQuote(Arc<DotenvSourceLocation>),
+	/// The closing quote of a value is followed by characters other than
+	/// white-space and a comment.
+	TrailingCharacters(Arc<DotenvSourceLocation>),
+	/// A variable reference of the form `${VAR}` is missing its closing brace.
+	InvalidReference(Arc<DotenvSourceLocation>),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::MissingKeyValueDelimiter(location) => write!(f, "Missing value for key in {}", location),
+			Error::InvalidKey(key, location) => write!(f, "Invalid key '{}' in {}", key, location),
+			Error::UnterminatedQuote(location) => write!(f, "Missing closing quote in {}", location),
+			Error::TrailingCharacters(location) => write!(f, "Unexpected characters after closing quote in {}", location),
+			Error::InvalidReference(location) => write!(f, "Missing closing brace of variable reference in {}", location),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Source location for the ConfigDotenv configuration source.
+/// This value is used to store the source of every configuration value for
+/// use in error messages.
+#[derive(Debug)]
+pub struct DotenvSourceLocation {
+	source_name: String,
+	line_start: usize,
+	line_end: usize
+}
+
+impl DotenvSourceLocation {
+	fn new(source_name: &str, line_start: usize, line_end: usize) -> Arc<Self> {
+		Arc::new(Self {
+			source_name: source_name.to_owned(),
+			line_start,
+			line_end
+		})
+	}
+}
+
+impl fmt::Display for DotenvSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.line_start == self.line_end {
+			write!(f, "dotenv:{}:{}", self.source_name, self.line_start)
+		} else {
+			write!(f, "dotenv:{}:{}-{}", self.source_name, self.line_start, self.line_end)
+		}
+	}
+}
+
+impl SourceLocation for DotenvSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.source_name)
+	}
+
+	fn line(&self) -> Option<usize> {
+		Some(self.line_start)
+	}
+}
+
+/// Parser state.
+struct Parser<'a> {
+	chars: Peekable<Chars<'a>>,
+	source_name: &'a str,
+	line: usize,
+	/// Values of the keys parsed so far. Used to resolve variable references.
+	variables: HashMap<String, String>
+}
+
+impl <'a> Parser<'a> {
+	fn next(&mut self) -> Option<char> {
+		let c = self.chars.next();
+		if c == Some('\n') {
+			self.line += 1;
+		}
+
+		c
+	}
+
+	fn location(&self, line_start: usize) -> Arc<DotenvSourceLocation> {
+		DotenvSourceLocation::new(self.source_name, line_start, self.line)
+	}
+
+	fn skip_blank(&mut self) {
+		while let Some(' ') | Some('\t') = self.chars.peek() {
+			self.next();
+		}
+	}
+
+	fn skip_line(&mut self) {
+		while let Some(c) = self.next() {
+			if c == '\n' {
+				break;
+			}
+		}
+	}
+
+	/// Parses the next assignment. Returns `None` at the end of the file.
+	fn assignment(&mut self) -> Result<Option<(String, String, Arc<DotenvSourceLocation>)>, Error> {
+		loop {
+			self.skip_blank();
+			match self.chars.peek() {
+				None => return Ok(None),
+				Some('#') | Some('\r') | Some('\n') => self.skip_line(),
+				Some(_) => break
+			}
+		}
+
+		let line_start = self.line;
+
+		let mut key = String::new();
+		while let Some(c) = self.chars.peek().copied().filter(|c| !matches!(c, '=' | '\n')) {
+			key.push(c);
+			self.next();
+		}
+
+		if self.next() != Some('=') {
+			return Err(Error::MissingKeyValueDelimiter(DotenvSourceLocation::new(self.source_name, line_start, line_start)));
+		}
+
+		let key = key.trim_end();
+		let key = key.strip_prefix("export").filter(|k| k.starts_with([' ', '\t'])).map_or(key, str::trim_start).to_owned();
+		if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+			return Err(Error::InvalidKey(key, DotenvSourceLocation::new(self.source_name, line_start, line_start)));
+		}
+
+		self.skip_blank();
+		let value = match self.chars.peek() {
+			Some('\'') => { self.next(); self.single_quoted(line_start)? },
+			Some('"') => { self.next(); self.double_quoted(line_start)? },
+			_ => self.unquoted(line_start)?
+		};
+
+		let location = self.location(line_start);
+
+		// Skip the rest of the line. It can only contain a comment.
+		self.skip_blank();
+		if !matches!(self.chars.peek(), None | Some('#') | Some('\r') | Some('\n')) {
+			return Err(Error::TrailingCharacters(DotenvSourceLocation::new(self.source_name, self.line, self.line)));
+		}
+		self.skip_line();
+
+		Ok(Some((key, value, location)))
+	}
+
+	fn unquoted(&mut self, line_start: usize) -> Result<String, Error> {
+		let mut raw = String::new();
+		while let Some(c) = self.chars.peek().copied().filter(|c| *c != '\n') {
+			if c == '#' && (raw.is_empty() || raw.ends_with([' ', '\t'])) {
+				break;
+			}
+
+			raw.push(c);
+			self.next();
+		}
+
+		let mut value = String::new();
+		let mut chars = raw.trim().chars().peekable();
+		while let Some(c) = chars.next() {
+			if c == '$' {
+				self.substitute(&mut chars, &mut value, line_start)?;
+			} else {
+				value.push(c);
+			}
+		}
+
+		Ok(value)
+	}
+
+	fn single_quoted(&mut self, line_start: usize) -> Result<String, Error> {
+		let mut value = String::new();
+
+		loop {
+			match self.next() {
+				Some('\'') => return Ok(value),
+				Some(c) => value.push(c),
+				None => return Err(Error::UnterminatedQuote(self.location(line_start)))
+			}
+		}
+	}
+
+	fn double_quoted(&mut self, line_start: usize) -> Result<String, Error> {
+		let mut raw = String::new();
+
+		// Collect the raw value first to be able to resolve the variable references.
+		loop {
+			match self.next() {
+				Some('"') => break,
+				Some('\\') => {
+					raw.push('\\');
+					if let Some(c) = self.next() {
+						raw.push(c);
+					}
+				},
+				Some(c) => raw.push(c),
+				None => return Err(Error::UnterminatedQuote(self.location(line_start)))
+			}
+		}
+
+		let mut value = String::new();
+		let mut chars = raw.chars().peekable();
+		while let Some(c) = chars.next() {
+			match c {
+				'\\' => match chars.next() {
+					Some('n') => value.push('\n'),
+					Some('r') => value.push('\r'),
+					Some('t') => value.push('\t'),
+					Some(c @ ('"' | '\\' | '$')) => value.push(c),
+					Some(c) => { value.push('\\'); value.push(c); },
+					None => value.push('\\')
+				},
+				'$' => self.substitute(&mut chars, &mut value, line_start)?,
+				c => value.push(c)
+			}
+		}
+
+		Ok(value)
+	}
+
+	/// Resolves a variable reference. The dollar sign was already consumed.
+	/// A dollar sign that does not start a reference is kept.
+	fn substitute(&self, chars: &mut Peekable<Chars>, value: &mut String, line_start: usize) -> Result<(), Error> {
+		let braced = chars.peek() == Some(&'{');
+		if braced {
+			chars.next();
+		}
+
+		let mut name = String::new();
+		while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_alphanumeric() || *c == '_') {
+			name.push(c);
+			chars.next();
+		}
+
+		if braced {
+			if chars.next() != Some('}') {
+				return Err(Error::InvalidReference(self.location(line_start)));
+			}
+		} else if name.is_empty() {
+			value.push('$');
+			return Ok(());
+		}
+
+		// Undefined variables are replaced by an empty string.
+		if let Some(resolved) = self.variables.get(&name).cloned().or_else(|| env::var(&name).ok()) {
+			value.push_str(&resolved);
+		}
+
+		Ok(())
+	}
+}
+
+/// Implements the dotenv source.
+pub struct ConfigDotenv {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigDotenv {
+	/// Parses a dotenv file into configuration information.
+	///
+	/// Any instance of a struct implementing `Read` can be passed to the parser.
+	/// As the second parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	///
+	/// The method returns a new `ConfigDotenv` instance or an error if the
+	/// file could not be parsed.
+	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(conf_source, source_name, &ConfPath::default())
+	}
+
+	/// Parses a dotenv file and fills a `ConfPath` with the contained keys.
+	///
+	/// This method works like [`ConfigText::with_path`](super::text::ConfigText::with_path).
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the file.
+	pub fn with_path(mut conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		let mut content = String::new();
+		conf_source.read_to_string(&mut content)?;
+
+		let mut parser = Parser {
+			chars: content.chars().peekable(),
+			source_name,
+			line: 1,
+			variables: HashMap::default()
+		};
+
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+
+		while let Some((key, value, location)) = parser.assignment()? {
+			let path = path_root.push(&key);
+			let mut item = StringItem::new(path.clone());
+			item.push(Value::new(value.clone(), location));
+
+			// The last value wins.
+			conf.items.insert(path, item);
+			parser.variables.insert(key, value);
+		}
+
+		Ok(Box::new(conf))
+	}
+}
+
+impl Source for ConfigDotenv {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn prepare_test_config(dotenv: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigDotenv::new(dotenv.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	fn value(c: &Config, key: &str) -> String {
+		c.get(ConfPath::from(&[key])).value().unwrap()
+	}
+
+	#[test]
+	fn parsing() {
+		let c = prepare_test_config(r#"
+# Comment
+PLAIN=value
+SPACED = spaced value   # Comment
+export EXPORTED=yes
+exported_name=no
+HASH=a#b
+SINGLE='$PLAIN \n'
+DOUBLE="a\tb \"c\" \$PLAIN"
+MULTI="line 1
+line 2"
+REF=${PLAIN}-$PLAIN-$
+QUOTED_REF="${SPACED}"
+QUOTED_COMMENT='x'	# Comment
+EMPTY=
+PLAIN=override
+"#);
+
+		assert_eq!(value(&c, "PLAIN"), "override");
+		assert_eq!(value(&c, "SPACED"), "spaced value");
+		assert_eq!(value(&c, "EXPORTED"), "yes");
+		assert_eq!(value(&c, "exported_name"), "no");
+		assert_eq!(value(&c, "HASH"), "a#b");
+		assert_eq!(value(&c, "SINGLE"), "$PLAIN \\n");
+		assert_eq!(value(&c, "DOUBLE"), "a\tb \"c\" $PLAIN");
+		assert_eq!(value(&c, "MULTI"), "line 1\nline 2");
+		assert_eq!(value(&c, "REF"), "value-value-$");
+		assert_eq!(value(&c, "QUOTED_COMMENT"), "x");
+		assert_eq!(value(&c, "QUOTED_REF"), "spaced value");
+		assert_eq!(value(&c, "EMPTY"), "");
+	}
+
+	#[test]
+	fn source_location() {
+		let c = prepare_test_config("A=x\nB=\"1\n2\"\n");
+
+		let error = (c.get(ConfPath::from(&["A"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'dotenv:myfile:1'");
+
+		let error = (c.get(ConfPath::from(&["B"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'dotenv:myfile:2-3'");
+	}
+
+	#[test]
+	fn errors() {
+		fn parse_error(dotenv: &str) -> String {
+			ConfigDotenv::new(dotenv.as_bytes(), "myfile").err().unwrap().to_string()
+		}
+
+		assert_eq!(parse_error("A=1\nB\n"), "Missing value for key in dotenv:myfile:2");
+		assert_eq!(parse_error("A B=1\n"), "Invalid key 'A B' in dotenv:myfile:1");
+		assert_eq!(parse_error("A='open\n"), "Missing closing quote in dotenv:myfile:1-2");
+		assert_eq!(parse_error("A=1\nB=\"x\" junk\n"), "Unexpected characters after closing quote in dotenv:myfile:2");
+		assert_eq!(parse_error("A='multi\nline'x\n"), "Unexpected characters after closing quote in dotenv:myfile:2");
+		assert_eq!(parse_error("A=${B\n"), "Missing closing brace of variable reference in dotenv:myfile:1");
+	}
+
+	#[test]
+	fn undefined_variable() {
+		let c = prepare_test_config("PASSWORD=\"pa$JUSTCONFIG_UNDEFINED_VARIABLE\"\nBRACED=a${JUSTCONFIG_UNDEFINED_VARIABLE}b\nQUOTED='pa$word'\n");
+
+		assert_eq!(value(&c, "PASSWORD"), "pa");
+		assert_eq!(value(&c, "BRACED"), "ab");
+		assert_eq!(value(&c, "QUOTED"), "pa$word");
+	}
+}
@@ -30,6 +30,47 @@
 //! // Read the path from the environment
 //! let path: String = conf.get(ConfPath::from(&["Path"])).value().unwrap();
 //! ```
+//!
+//! ## Prefix mapping
+//!
+//! Creating a mapping for every variable gets tedious for applications with a
+//! lot of settings. [`with_prefix`](Env::with_prefix) creates an environment
+//! source that maps all variables starting with a common prefix onto
+//! configuration paths. The name of the variable is constructed from the
+//! prefix, an underscore and the components of the configuration path joined
+//! by a separator. The components are converted to upper case. The
+//! configuration path `database.pool_size` is read from the variable
+//! `MYAPP_DATABASE__POOL_SIZE` if the prefix is `MYAPP` and the separator is
+//! `__`.
+//!
+//! If the variable does not exist, the variables with the suffixes `_0`, `_1`,
+//! ... are read as multiple values of the configuration item. The first
+//! missing index ends the list.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::env::Env;
+//! # std::env::set_var("MYAPP_DATABASE__POOL_SIZE", "10");
+//! # std::env::set_var("MYAPP_PLUGINS_0", "auth");
+//! # std::env::set_var("MYAPP_PLUGINS_1", "cache");
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(Env::with_prefix("MYAPP", "__"));
+//!
+//! // Read from MYAPP_DATABASE__POOL_SIZE
+//! let pool_size: u32 = conf.get(ConfPath::from(&["database", "pool_size"])).value().unwrap();
+//! // Read from MYAPP_PLUGINS_0 and MYAPP_PLUGINS_1
+//! let plugins: Vec<String> = conf.get(ConfPath::from(&["plugins"])).values(..).unwrap();
+//!
+//! assert_eq!(pool_size, 10);
+//! assert_eq!(plugins, ["auth", "cache"]);
+//! ```
+//!
+//! Prefix mapped variables take part in key enumeration. Their names are
+//! converted to lower case to form the configuration paths. The case folding
+//! can be disabled with [`set_case_folding`](Env::set_case_folding).
 use crate::source::Source;
 use crate::item::{SourceLocation, StringItem, Value};
 use crate::confpath::ConfPath;
@@ -37,7 +78,7 @@ use std::ffi::{OsStr, OsString};
 use std::collections::hash_map::HashMap;
 use std::fmt;
 use std::env;
-use std::rc::Rc;
+use std::sync::Arc;
 
 /// Source location for the Env configuration source.
 /// 
@@ -49,8 +90,8 @@ struct EnvSourceLocation {
 }
 
 impl EnvSourceLocation {
-	pub fn new(env_name: &OsStr) -> Rc<Self> {
-		Rc::new(Self {
+	pub fn new(env_name: &OsStr) -> Arc<Self> {
+		Arc::new(Self {
 			env_name: env_name.to_owned()
 		})
 	}
@@ -64,9 +105,48 @@ impl fmt::Display for EnvSourceLocation {
 
 impl SourceLocation for EnvSourceLocation {}
 
+/// Settings for mapping environment variables by their prefix.
+struct PrefixMapping {
+	prefix: String,
+	separator: String,
+	case_folding: bool
+}
+
+impl PrefixMapping {
+	/// Returns the start of the names of all mapped variables.
+	fn name_start(&self) -> String {
+		if self.prefix.is_empty() {
+			String::new()
+		} else {
+			format!("{}_", self.prefix)
+		}
+	}
+
+	/// Returns the name of the variable for the configuration path.
+	fn env_name(&self, key: &ConfPath) -> String {
+		let components: Vec<String> = key.component_names().into_iter()
+			.map(|component| if self.case_folding { component.to_uppercase() } else { component })
+			.collect();
+
+		self.name_start() + &components.join(&self.separator)
+	}
+
+	/// Returns the configuration path for the name of a variable without the
+	/// prefix or `None` if the name contains empty components.
+	fn key(&self, name: &str) -> Option<ConfPath> {
+		let name = if self.case_folding { name.to_lowercase() } else { name.to_owned() };
+		if name.split(&self.separator).any(str::is_empty) {
+			return None;
+		}
+
+		Some(ConfPath::default().push_all(name.split(&self.separator)))
+	}
+}
+
 /// Implements the environment source.
 pub struct Env {
-	env_mapping: HashMap<ConfPath, OsString>
+	env_mapping: HashMap<ConfPath, OsString>,
+	prefix_mapping: Option<PrefixMapping>
 }
 
 impl Env {
@@ -81,19 +161,122 @@ impl Env {
 	/// See the [`env`](mod@env) module for more information.
 	pub fn new(env_mapping: &[(ConfPath, &OsStr)]) -> Box<Self> {
 		Box::new(Self {
-			env_mapping: env_mapping.iter().map(|m| (m.0.clone(), m.1.to_owned())).collect()
+			env_mapping: env_mapping.iter().map(|m| (m.0.clone(), m.1.to_owned())).collect(),
+			prefix_mapping: None
+		})
+	}
+
+	/// Creates a new environment source that maps all variables starting with
+	/// `prefix` onto configuration paths.
+	///
+	/// The components of the configuration path are joined by `separator` to
+	/// form the name of the environment variable. An underscore is placed
+	/// between the prefix and the first component. If the prefix is empty, all
+	/// environment variables are mapped.
+	///
+	/// See [Prefix mapping](mod@env#prefix-mapping) for more information.
+	pub fn with_prefix(prefix: &str, separator: &str) -> Box<Self> {
+		Box::new(Self {
+			env_mapping: HashMap::default(),
+			prefix_mapping: Some(PrefixMapping {
+				prefix: prefix.to_owned(),
+				separator: separator.to_owned(),
+				case_folding: true
+			})
 		})
 	}
+
+	/// Enables or disables the case folding of prefix mapped variables.
+	///
+	/// If case folding is enabled (the default) the components of the
+	/// configuration path are converted to upper case to get the name of the
+	/// environment variable and names of environment variables are converted to
+	/// lower case when enumerating keys. If it is disabled, the names are used
+	/// unchanged.
+	///
+	/// This setting has no effect for environment sources created by
+	/// [`new`](Self::new).
+	pub fn set_case_folding(&mut self, case_folding: bool) {
+		if let Some(prefix_mapping) = &mut self.prefix_mapping {
+			prefix_mapping.case_folding = case_folding;
+		}
+	}
+
+	/// Reads the prefix mapped variable for the key. If it does not exist, the
+	/// indexed variables are read as multiple values.
+	fn get_prefixed(prefix_mapping: &PrefixMapping, key: ConfPath) -> Option<StringItem> {
+		let env_name = prefix_mapping.env_name(&key);
+
+		if let Ok(value) = env::var(&env_name) {
+			return Some(StringItem::from(key, &[Value::new(value, EnvSourceLocation::new(OsStr::new(&env_name)))]));
+		}
+
+		let values: Vec<_> = (0..)
+			.map(|index| format!("{}_{}", env_name, index))
+			.map_while(|indexed_name| env::var(&indexed_name).ok().map(|value| Value::new(value, EnvSourceLocation::new(OsStr::new(&indexed_name)))))
+			.collect();
+
+		if values.is_empty() {
+			None
+		} else {
+			Some(StringItem::from(key, &values))
+		}
+	}
+
+	/// Returns the keys of all prefix mapped variables.
+	fn prefixed_keys(prefix_mapping: &PrefixMapping) -> Vec<ConfPath> {
+		let name_start = prefix_mapping.name_start();
+		let mut keys = Vec::new();
+
+		// env::vars panics on variables that are not valid unicode. They can not
+		// be read by get either, so they are skipped.
+		let names = env::vars_os().filter_map(|(name, value)| value.into_string().ok().and(name.into_string().ok()));
+
+		for name in names {
+			if let Some(name) = name.strip_prefix(&name_start) {
+				// Indexed variables belong to the key without the index, if they can be read through it.
+				let indexed_key = name.rsplit_once('_')
+					.filter(|(_, index)| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+					.and_then(|(base_name, index)| Some((prefix_mapping.key(base_name)?, index.parse::<usize>().ok()?)))
+					.filter(|(key, index)| {
+						let env_name = prefix_mapping.env_name(key);
+						env::var_os(&env_name).is_none() && (0..=*index).all(|i| env::var(format!("{}_{}", env_name, i)).is_ok())
+					})
+					.map(|(key, _)| key);
+
+				if let Some(key) = indexed_key.or_else(|| prefix_mapping.key(name)) {
+					if !keys.contains(&key) {
+						keys.push(key);
+					}
+				}
+			}
+		}
+
+		keys
+	}
 }
 
 impl Source for Env {
 	fn get(&self, key: ConfPath) -> Option<StringItem> {
 		if let Some(env_name) = self.env_mapping.get(&key) {
 			env::var(env_name).ok().map(|v| StringItem::from(key, &[Value::new(v, EnvSourceLocation::new(env_name))]))
+		} else if let Some(prefix_mapping) = &self.prefix_mapping {
+			Self::get_prefixed(prefix_mapping, key)
 		} else {
 			None
 		}
 	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		// Only variables that are set (and valid unicode) are returned by `get`.
+		let mut keys: Vec<ConfPath> = self.env_mapping.iter().filter(|(_, env_name)| env::var(env_name).is_ok()).map(|(key, _)| key.clone()).collect();
+
+		if let Some(prefix_mapping) = &self.prefix_mapping {
+			keys.extend(Self::prefixed_keys(prefix_mapping));
+		}
+
+		keys
+	}
 }
 
 #[cfg(test)]
@@ -138,4 +321,67 @@ mod tests {
 
 		(c.get(ConfPath::from(&["testC"])).value() as Result<String, ConfigError>).unwrap();
 	}
+
+	#[test]
+	fn keys() {
+		let c = prepare_test_config();
+
+		assert_eq!(c.keys(), [ConfPath::from(&["testA"])]);
+	}
+
+	#[test]
+	fn prefix() {
+		env::set_var("JCTEST_PREFIX_DATABASE__POOL_SIZE", "10");
+		env::set_var("JCTEST_PREFIX_PLUGINS_0", "a");
+		env::set_var("JCTEST_PREFIX_PLUGINS_1", "b");
+		env::set_var("JCTEST_PREFIX_PLUGINS_3", "d");
+		env::set_var("JCTEST_PREFIX_RETRY_2", "5");
+		env::set_var("JCTEST_PREFIX_INVALID____KEY", "x");
+
+		let mut c = Config::default();
+		c.add_source(Env::with_prefix("JCTEST_PREFIX", "__"));
+
+		assert_eq!((c.get(ConfPath::from(&["database", "pool_size"])).value() as Result<u32, ConfigError>).unwrap(), 10);
+		assert_eq!((c.get(ConfPath::from(&["plugins"])).values(..) as Result<Vec<String>, ConfigError>).unwrap(), ["a", "b"]);
+		assert_eq!((c.get(ConfPath::from(&["retry_2"])).value() as Result<u32, ConfigError>).unwrap(), 5);
+
+		let error = (c.get(ConfPath::from(&["plugins"])).value() as Result<String, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "More than 1 value found for key plugins@['env:JCTEST_PREFIX_PLUGINS_0', 'env:JCTEST_PREFIX_PLUGINS_1']");
+
+		assert_eq!(c.keys(), [
+			ConfPath::from(&["database", "pool_size"]),
+			ConfPath::from(&["plugins"]),
+			ConfPath::from(&["plugins_3"]),
+			ConfPath::from(&["retry_2"])
+		]);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn prefix_non_unicode() {
+		use std::os::unix::ffi::OsStrExt;
+
+		env::set_var("JCTEST_NON_UNICODE_VALID", "value");
+		env::set_var("JCTEST_NON_UNICODE_VALUE", OsStr::from_bytes(b"\xff"));
+		env::set_var(OsStr::from_bytes(b"JCTEST_NON_UNICODE_\xff"), "value");
+
+		let mut c = Config::default();
+		c.add_source(Env::with_prefix("JCTEST_NON_UNICODE", "__"));
+
+		assert_eq!(c.keys(), [ConfPath::from(&["valid"])]);
+	}
+
+	#[test]
+	fn prefix_case_folding() {
+		env::set_var("JCTEST_CASE_Mixed_Case", "value");
+
+		let mut env = Env::with_prefix("JCTEST_CASE", "_");
+		env.set_case_folding(false);
+
+		let mut c = Config::default();
+		c.add_source(env);
+
+		assert_eq!((c.get(ConfPath::from(&["Mixed", "Case"])).value() as Result<String, ConfigError>).unwrap(), "value");
+		assert_eq!(c.keys(), [ConfPath::from(&["Mixed", "Case"])]);
+	}
 }
\ No newline at end of file
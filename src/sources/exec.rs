@@ -0,0 +1,436 @@
+//! Command output source.
+//!
+//! This source runs an external command and parses its standard output into
+//! configuration information. This allows settings to be supplied by helper
+//! executables, for example to query cloud instance metadata or a secret store.
+//!
+//! The command is passed as a list of arguments and is executed directly. No
+//! shell is involved. If the command does not finish within the timeout, it is
+//! killed. The timeout includes reading the output of the command. The
+//! standard output is limited to 16 MiB. The standard error output reported in
+//! error messages is truncated to 64 KiB.
+//!
+//! The output is parsed by a parser function that creates another
+//! configuration source. [`text`](Exec::text) and [`json`](Exec::json) use
+//! the parsers of the [`text`](super::text) and [`json`](super::json) sources.
+//!
+//! The command is run once when the source is created and its result is
+//! cached. Calling [`reload`](crate::Config::reload) runs the command again.
+//! If the output changed, the configuration is replaced. If the command fails,
+//! the old configuration stays active and the error is returned by `reload`.
+//!
+//! ```rust
+//! # #[cfg(unix)] {
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::exec::Exec;
+//! use std::time::Duration;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(Exec::text(&["printf", "region=eu-central-1\\n"], Duration::from_secs(5)).unwrap());
+//!
+//! let region: String = conf.get(ConfPath::from(&["region"])).value().unwrap();
+//! assert_eq!(region, "eu-central-1");
+//! # }
+//! ```
+//!
+//! The source locations of the values contain the name of the program and the
+//! line of the output if the parser supplies it (`exec:/usr/libexec/myapp-meta:3`).
+use crate::source::Source;
+use crate::item::{SourceLocation, StringItem, Value};
+use crate::confpath::ConfPath;
+use crate::sources::text::ConfigText;
+use crate::sources::json::ConfigJson;
+
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Arc, RwLock, PoisonError};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use std::thread;
+use std::fmt;
+
+/// Interval for checking if the command exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Maximum size of the standard output of the command.
+const MAX_OUTPUT_SIZE: usize = 16 * 1024 * 1024;
+
+/// Maximum size of the standard error output kept for error messages.
+const MAX_STDERR_SIZE: usize = 64 * 1024;
+
+/// Function parsing the output of the command into a configuration source.
+/// The second parameter contains the name of the program.
+type Parser = dyn Fn(&[u8], &str) -> Result<Box<dyn Source>, Box<dyn std::error::Error + Send + Sync>> + Send + Sync;
+
+/// Enumeration containing errors that occur while running the command.
+#[derive(Debug)]
+pub enum Error {
+	/// The command does not contain a program to run.
+	EmptyCommand,
+	/// The program could not be started. The first parameter contains the
+	/// name of the program.
+	Spawn(String, std::io::Error),
+	/// The command did not finish or did not close its output within the
+	/// timeout. The first parameter contains the name of the program.
+	Timeout(String, Duration),
+	/// The standard output of the command exceeds the maximum size. The first
+	/// parameter contains the name of the program, the second one the maximum
+	/// size in bytes.
+	OutputTooLarge(String, usize),
+	/// The command exited with an unsuccessful status. The first parameter
+	/// contains the name of the program, the last parameter contains the
+	/// standard error output of the command.
+	Failed(String, ExitStatus, String),
+	/// The output of the command could not be parsed. The first parameter
+	/// contains the name of the program.
+	Parse(String, Box<dyn std::error::Error + Send + Sync>),
+	/// An I/O error occurred while reading the output of the command.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::EmptyCommand => write!(f, "The command is empty"),
+			Error::Spawn(program, error) => write!(f, "Starting '{}' failed: {}", program, error),
+			Error::Timeout(program, timeout) => write!(f, "'{}' did not finish within {:?}", program, timeout),
+			Error::OutputTooLarge(program, size) => write!(f, "The output of '{}' exceeds {} bytes", program, size),
+			Error::Failed(program, status, stderr) => write!(f, "'{}' failed with {}: {}", program, status, stderr.trim_end()),
+			Error::Parse(program, error) => write!(f, "Parsing the output of '{}' failed: {}", program, error),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Spawn(_, source) => Some(source),
+			Error::Parse(_, source) => Some(source.as_ref()),
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Source location for the Exec configuration source.
+///
+/// This value is used to store the source of every configuration value for
+/// use in error messages. It wraps the source location created by the parser.
+#[derive(Debug)]
+pub struct ExecSourceLocation {
+	program: String,
+	parser_location: Arc<dyn SourceLocation>
+}
+
+impl ExecSourceLocation {
+	fn new(program: &str, parser_location: Arc<dyn SourceLocation>) -> Arc<Self> {
+		Arc::new(Self {
+			program: program.to_owned(),
+			parser_location
+		})
+	}
+}
+
+impl fmt::Display for ExecSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.parser_location.line() {
+			Some(line) => write!(f, "exec:{}:{}", self.program, line),
+			None => write!(f, "exec:{}", self.program)
+		}
+	}
+}
+
+impl SourceLocation for ExecSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.program)
+	}
+
+	fn line(&self) -> Option<usize> {
+		self.parser_location.line()
+	}
+}
+
+/// The command to run and the parser for its output.
+struct Runner {
+	command: Vec<OsString>,
+	program: String,
+	timeout: Duration,
+	parser: Box<Parser>
+}
+
+/// Implements the command output source.
+pub struct Exec {
+	runner: Runner,
+	/// The last output of the command and the source created from it.
+	state: RwLock<(Vec<u8>, Box<dyn Source>)>
+}
+
+impl Exec {
+	/// Creates a new command output source.
+	///
+	/// The first element of `command` is the program to run, the remaining
+	/// elements are passed as arguments. The command is run immediately. Its
+	/// standard output is passed to the `parser` together with the name of the
+	/// program. The parser returns the configuration source that supplies the
+	/// configuration values.
+	///
+	/// The method returns an error if the command could not be run, did not
+	/// finish within `timeout`, exited with an unsuccessful status or the parser
+	/// failed.
+	pub fn new<S, P>(command: &[S], timeout: Duration, parser: P) -> Result<Box<Self>, Error>
+	where
+		S: AsRef<OsStr>,
+		P: Fn(&[u8], &str) -> Result<Box<dyn Source>, Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static
+	{
+		let command: Vec<OsString> = command.iter().map(|argument| argument.as_ref().to_owned()).collect();
+		let program = command.first().ok_or(Error::EmptyCommand)?.to_string_lossy().into_owned();
+
+		let runner = Runner {
+			command,
+			program,
+			timeout,
+			parser: Box::new(parser)
+		};
+
+		let output = runner.run()?;
+		let source = runner.parse(&output)?;
+
+		Ok(Box::new(Self {
+			runner,
+			state: RwLock::new((output, source))
+		}))
+	}
+
+	/// Creates a new command output source for a command that writes the text
+	/// format of the [`text`](super::text) source.
+	pub fn text<S: AsRef<OsStr>>(command: &[S], timeout: Duration) -> Result<Box<Self>, Error> {
+		Self::new(command, timeout, |output, program| {
+			let source: Box<dyn Source> = ConfigText::new(output, program)?;
+			Ok(source)
+		})
+	}
+
+	/// Creates a new command output source for a command that writes a JSON
+	/// document.
+	pub fn json<S: AsRef<OsStr>>(command: &[S], timeout: Duration) -> Result<Box<Self>, Error> {
+		Self::new(command, timeout, |output, program| {
+			let source: Box<dyn Source> = ConfigJson::new(output, program)?;
+			Ok(source)
+		})
+	}
+}
+
+impl Runner {
+	/// Runs the command and returns its standard output.
+	fn run(&self) -> Result<Vec<u8>, Error> {
+		let mut child = Command::new(&self.command[0])
+			.args(&self.command[1..])
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(|error| Error::Spawn(self.program.clone(), error))?;
+
+		// The pipes are read by separate threads to prevent the command from
+		// blocking if it writes more than fits into the pipe buffer.
+		let stdout = Self::read_pipe(child.stdout.take(), MAX_OUTPUT_SIZE);
+		let stderr = Self::read_pipe(child.stderr.take(), MAX_STDERR_SIZE);
+
+		let started = Instant::now();
+		let status = loop {
+			if let Some(status) = child.try_wait()? {
+				break status;
+			}
+
+			if started.elapsed() >= self.timeout {
+				let _ = child.kill();
+				let _ = child.wait();
+				return Err(Error::Timeout(self.program.clone(), self.timeout));
+			}
+
+			thread::sleep(POLL_INTERVAL);
+		};
+
+		// Processes started by the command can keep the pipes open after the
+		// command exited. Do not wait for them longer than the timeout.
+		let stdout = self.receive(&stdout, started)?;
+		let mut stderr = self.receive(&stderr, started)?;
+
+		if !status.success() {
+			stderr.truncate(MAX_STDERR_SIZE);
+			return Err(Error::Failed(self.program.clone(), status, String::from_utf8_lossy(&stderr).into_owned()));
+		}
+
+		if stdout.len() > MAX_OUTPUT_SIZE {
+			return Err(Error::OutputTooLarge(self.program.clone(), MAX_OUTPUT_SIZE));
+		}
+
+		Ok(stdout)
+	}
+
+	/// Reads the pipe within a separate thread. At most `limit + 1` bytes are
+	/// kept to detect if the output exceeds the limit. The remaining output is
+	/// discarded.
+	fn read_pipe(pipe: Option<impl Read + Send + 'static>, limit: usize) -> Receiver<io::Result<Vec<u8>>> {
+		let (sender, receiver) = mpsc::channel();
+
+		thread::spawn(move || {
+			let mut buffer = Vec::new();
+			let result = match pipe {
+				Some(mut pipe) => pipe.by_ref().take(limit as u64 + 1).read_to_end(&mut buffer)
+					.and_then(|_| io::copy(&mut pipe, &mut io::sink()))
+					.map(|_| buffer),
+				None => Ok(buffer)
+			};
+
+			// The receiver is gone if the timeout expired.
+			let _ = sender.send(result);
+		});
+
+		receiver
+	}
+
+	/// Waits for the output of a pipe until the timeout expires.
+	fn receive(&self, output: &Receiver<io::Result<Vec<u8>>>, started: Instant) -> Result<Vec<u8>, Error> {
+		match output.recv_timeout(self.timeout.saturating_sub(started.elapsed())) {
+			Ok(result) => Ok(result?),
+			Err(RecvTimeoutError::Timeout) => Err(Error::Timeout(self.program.clone(), self.timeout)),
+			// The reader thread panicked.
+			Err(RecvTimeoutError::Disconnected) => Ok(Vec::new())
+		}
+	}
+
+	fn parse(&self, output: &[u8]) -> Result<Box<dyn Source>, Error> {
+		(self.parser)(output, &self.program).map_err(|error| Error::Parse(self.program.clone(), error))
+	}
+}
+
+impl Source for Exec {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
+
+		state.1.get(key).map(|item| {
+			let values: Vec<_> = item.values().iter()
+				.map(|value| Value::new(value.value().clone(), ExecSourceLocation::new(&self.runner.program, value.source())))
+				.collect();
+
+			StringItem::from(item.key().clone(), &values)
+		})
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.state.read().unwrap_or_else(PoisonError::into_inner).1.keys()
+	}
+
+	fn reload(&self) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+		let output = self.runner.run()?;
+
+		if output == self.state.read().unwrap_or_else(PoisonError::into_inner).0 {
+			return Ok(false);
+		}
+
+		let source = self.runner.parse(&output)?;
+		*self.state.write().unwrap_or_else(PoisonError::into_inner) = (output, source);
+
+		Ok(true)
+	}
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use std::fs;
+
+	#[test]
+	fn text() {
+		let mut c = Config::default();
+		c.add_source(Exec::text(&["printf", "key=value\\nnumber=x\\n"], Duration::from_secs(10)).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["key"])).value() as Result<String, ConfigError>).unwrap(), "value");
+
+		let error = (c.get(ConfPath::from(&["number"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'exec:printf:2'");
+	}
+
+	#[test]
+	fn json() {
+		let mut c = Config::default();
+		c.add_source(Exec::json(&["printf", "{ \"a\": { \"b\": 1 } }"], Duration::from_secs(10)).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["a", "b"])).value() as Result<u32, ConfigError>).unwrap(), 1);
+	}
+
+	#[test]
+	fn errors() {
+		assert!(matches!(Exec::text::<&str>(&[], Duration::from_secs(10)), Err(Error::EmptyCommand)));
+		assert!(matches!(Exec::text(&["/justconfig/does/not/exist"], Duration::from_secs(10)), Err(Error::Spawn(_, _))));
+
+		match Exec::text(&["sh", "-c", "echo oops >&2; exit 3"], Duration::from_secs(10)) {
+			Err(Error::Failed(program, status, stderr)) => {
+				assert_eq!(program, "sh");
+				assert_eq!(status.code(), Some(3));
+				assert_eq!(stderr, "oops\n");
+			},
+			_ => panic!("Failing command not detected.")
+		}
+
+		match Exec::text(&["sleep", "10"], Duration::from_millis(100)) {
+			Err(Error::Timeout(program, _)) => assert_eq!(program, "sleep"),
+			_ => panic!("Timeout not detected.")
+		}
+
+		assert!(matches!(Exec::text(&["printf", "no delimiter\\n"], Duration::from_secs(10)), Err(Error::Parse(_, _))));
+
+		match Exec::text(&["head", "-c", "20000000", "/dev/zero"], Duration::from_secs(10)) {
+			Err(Error::OutputTooLarge(program, size)) => {
+				assert_eq!(program, "head");
+				assert_eq!(size, MAX_OUTPUT_SIZE);
+			},
+			_ => panic!("Output size not limited.")
+		}
+	}
+
+	#[test]
+	fn timeout_open_pipe() {
+		// The background process keeps the pipes open after the command exited.
+		let started = Instant::now();
+		match Exec::text(&["sh", "-c", "sleep 4 & echo key=value"], Duration::from_millis(500)) {
+			Err(Error::Timeout(program, _)) => assert_eq!(program, "sh"),
+			_ => panic!("Timeout not detected.")
+		}
+		assert!(started.elapsed() < Duration::from_secs(3));
+	}
+
+	#[test]
+	fn reload() {
+		let file = std::env::temp_dir().join(format!("justconfig-exec-{}.conf", std::process::id()));
+		fs::write(&file, "key=first\n").unwrap();
+
+		let mut c = Config::default();
+		c.add_source(Exec::text(&[OsStr::new("cat"), file.as_os_str()], Duration::from_secs(10)).unwrap());
+
+		assert!(c.reload().unwrap().is_empty());
+
+		fs::write(&file, "key=second\n").unwrap();
+		assert_eq!(c.reload().unwrap().len(), 1);
+		assert_eq!((c.get(ConfPath::from(&["key"])).value() as Result<String, ConfigError>).unwrap(), "second");
+
+		// A failing command keeps the old configuration.
+		fs::remove_file(&file).unwrap();
+		assert!(c.reload().is_err());
+		assert_eq!((c.get(ConfPath::from(&["key"])).value() as Result<String, ConfigError>).unwrap(), "second");
+	}
+}
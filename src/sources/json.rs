@@ -0,0 +1,547 @@
+//! JSON source.
+//!
+//! This source parses a JSON document into configuration information. This
+//! allows the output of tools emitting JSON to be layered with other
+//! configuration sources without converting it first.
+//!
+//! Any struct that implements `Read` can be used as a source for configuration
+//! values. The document must be UTF-8 encoded.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::json::ConfigJson;
+//!
+//! let json = r#"{ "server": { "port": 8080, "hosts": ["a", "b"] } }"#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigJson::new(json.as_bytes(), "myconfig.json").unwrap());
+//!
+//! let port: u16 = conf.get(conf.root().push_all(["server", "port"])).value().unwrap();
+//! let hosts: Vec<String> = conf.get(conf.root().push_all(["server", "hosts"])).values(..).unwrap();
+//!
+//! assert_eq!(port, 8080);
+//! assert_eq!(hosts, ["a", "b"]);
+//! ```
+//!
+//! ## Mapping
+//!
+//! The JSON document is mapped onto configuration paths by the following
+//! rules:
+//!
+//! * The document must contain an object. The names of its members are the
+//!   first component of the configuration paths.
+//! * Nested objects add a path component for every member.
+//! * Strings, numbers and booleans become the value of the configuration item.
+//!   Numbers are stored exactly like they were written in the document.
+//! * Arrays become multiple values of the same configuration item. Objects
+//!   within an array are stored below a sub-path named like their index within
+//!   the array (`0`, `1`, ...). An array that only contains objects does not
+//!   create an item for the array itself. Empty arrays result in a
+//!   configuration item without values. Arrays must not contain other arrays.
+//! * `null` results in a configuration item without values. This hides the
+//!   values of configuration sources with a lower priority.
+//!
+//! If an object contains the same member multiple times, the last one wins.
+//!
+//! The source locations of the values contain the name of the source, the line
+//! and the column the value starts at.
+use crate::source::Source;
+use crate::item::{StringItem, Value};
+use crate::confpath::ConfPath;
+use super::position::{self, PositionSourceLocation};
+
+use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::fmt;
+
+/// Maximum nesting depth of objects and arrays.
+const MAX_NESTING_DEPTH: usize = 128;
+
+/// Enumeration containing parse errors.
+#[derive(Debug)]
+pub enum Error {
+	/// An unexpected character was found. The first parameter contains the
+	/// character.
+	UnexpectedCharacter(char, Arc<JsonSourceLocation>),
+	/// The document ended unexpectedly.
+	UnexpectedEnd(Arc<JsonSourceLocation>),
+	/// A string contains an invalid escape sequence.
+	InvalidEscape(Arc<JsonSourceLocation>),
+	/// A number is not formatted correctly.
+	InvalidNumber(Arc<JsonSourceLocation>),
+	/// The document does not contain an object at the top level.
+	NotAnObject(Arc<JsonSourceLocation>),
+	/// Objects and arrays are nested too deeply.
+	TooDeep(Arc<JsonSourceLocation>),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::UnexpectedCharacter(c, location) => write!(f, "Unexpected character {:?} in {}", c, location),
+			Error::UnexpectedEnd(location) => write!(f, "Unexpected end of document in {}", location),
+			Error::InvalidEscape(location) => write!(f, "Invalid escape sequence in {}", location),
+			Error::InvalidNumber(location) => write!(f, "Invalid number in {}", location),
+			Error::NotAnObject(location) => write!(f, "The document must contain an object in {}", location),
+			Error::TooDeep(location) => write!(f, "Maximum nesting depth exceeded in {}", location),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Source location for the ConfigJson configuration source.
+///
+/// The location is displayed as `json:name:line:column`.
+pub type JsonSourceLocation = PositionSourceLocation;
+
+/// Character stream that keeps track of the current position.
+struct Reader<'a>(position::Reader<'a>);
+
+impl <'a> Reader<'a> {
+	fn location(&self) -> Arc<JsonSourceLocation> {
+		self.0.location()
+	}
+
+	fn peek(&mut self) -> Option<char> {
+		self.0.peek()
+	}
+
+	fn next(&mut self) -> Result<char, Error> {
+		self.0.next().ok_or_else(|| Error::UnexpectedEnd(self.location()))
+	}
+
+	fn skip_whitespace(&mut self) {
+		while let Some(' ') | Some('\t') | Some('\r') | Some('\n') = self.peek() {
+			let _ = self.next();
+		}
+	}
+
+	/// Skips white-space and consumes the expected character.
+	fn expect(&mut self, expected: char) -> Result<(), Error> {
+		self.skip_whitespace();
+
+		let location = self.location();
+		match self.next()? {
+			c if c == expected => Ok(()),
+			c => Err(Error::UnexpectedCharacter(c, location))
+		}
+	}
+
+	/// Consumes a keyword like `true`.
+	fn keyword(&mut self, keyword: &str) -> Result<(), Error> {
+		for expected in keyword.chars() {
+			let location = self.location();
+			match self.next()? {
+				c if c == expected => (),
+				c => return Err(Error::UnexpectedCharacter(c, location))
+			}
+		}
+
+		Ok(())
+	}
+
+	fn string(&mut self) -> Result<String, Error> {
+		self.expect('"')?;
+
+		let mut value = String::new();
+		loop {
+			let location = self.location();
+			match self.next()? {
+				'"' => return Ok(value),
+				'\\' => match self.next()? {
+					'"' => value.push('"'),
+					'\\' => value.push('\\'),
+					'/' => value.push('/'),
+					'b' => value.push('\u{8}'),
+					'f' => value.push('\u{c}'),
+					'n' => value.push('\n'),
+					'r' => value.push('\r'),
+					't' => value.push('\t'),
+					'u' => {
+						let high = self.hex_escape(&location)?;
+
+						let code_point = if (0xD800..0xDC00).contains(&high) {
+							// UTF-16 surrogate pair
+							self.keyword("\\u").map_err(|_| Error::InvalidEscape(location.clone()))?;
+							let low = self.hex_escape(&location)?;
+							if !(0xDC00..0xE000).contains(&low) {
+								return Err(Error::InvalidEscape(location));
+							}
+
+							0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+						} else {
+							high
+						};
+
+						value.push(std::char::from_u32(code_point).ok_or_else(|| Error::InvalidEscape(location.clone()))?);
+					},
+					_ => return Err(Error::InvalidEscape(location))
+				},
+				c if (c as u32) < 0x20 => return Err(Error::UnexpectedCharacter(c, location)),
+				c => value.push(c)
+			}
+		}
+	}
+
+	fn hex_escape(&mut self, location: &Arc<JsonSourceLocation>) -> Result<u32, Error> {
+		let mut code_point = 0;
+		for _ in 0..4 {
+			let digit = self.next()?.to_digit(16).ok_or_else(|| Error::InvalidEscape(location.clone()))?;
+			code_point = code_point * 16 + digit;
+		}
+
+		Ok(code_point)
+	}
+
+	fn number(&mut self) -> Result<String, Error> {
+		let location = self.location();
+
+		let mut value = String::new();
+		while let Some(c) = self.peek() {
+			if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E') {
+				value.push(self.next()?);
+			} else {
+				break;
+			}
+		}
+
+		if Self::is_valid_number(&value) {
+			Ok(value)
+		} else {
+			Err(Error::InvalidNumber(location))
+		}
+	}
+
+	/// Checks the number against the JSON number grammar.
+	fn is_valid_number(number: &str) -> bool {
+		fn digits(s: &str) -> usize {
+			s.chars().take_while(char::is_ascii_digit).count()
+		}
+
+		let rest = number.strip_prefix('-').unwrap_or(number);
+
+		let int_len = digits(rest);
+		if int_len == 0 || (int_len > 1 && rest.starts_with('0')) {
+			return false;
+		}
+		let mut rest = &rest[int_len..];
+
+		if let Some(fraction) = rest.strip_prefix('.') {
+			let fraction_len = digits(fraction);
+			if fraction_len == 0 {
+				return false;
+			}
+			rest = &fraction[fraction_len..];
+		}
+
+		if let Some(exponent) = rest.strip_prefix('e').or_else(|| rest.strip_prefix('E')) {
+			let exponent = exponent.strip_prefix('+').or_else(|| exponent.strip_prefix('-')).unwrap_or(exponent);
+			let exponent_len = digits(exponent);
+			if exponent_len == 0 {
+				return false;
+			}
+			rest = &exponent[exponent_len..];
+		}
+
+		rest.is_empty()
+	}
+}
+
+/// Implements the JSON source.
+pub struct ConfigJson {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigJson {
+	/// Parses a JSON document into configuration information.
+	///
+	/// Any instance of a struct implementing `Read` can be passed to the parser.
+	/// As the second parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	///
+	/// The method returns a new `ConfigJson` instance or an error if the
+	/// document could not be parsed.
+	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(conf_source, source_name, &ConfPath::default())
+	}
+
+	/// Parses a JSON document and fills a `ConfPath` with the contained keys.
+	///
+	/// This method works like [`ConfigText::with_path`](super::text::ConfigText::with_path).
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the document.
+	pub fn with_path(mut conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		let mut document = String::new();
+		conf_source.read_to_string(&mut document)?;
+
+		let mut reader = Reader(position::Reader::new(&document, "json", source_name));
+
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+
+		reader.skip_whitespace();
+		if reader.peek() != Some('{') {
+			return Err(Error::NotAnObject(reader.location()));
+		}
+		conf.parse_object(&mut reader, path_root, 0)?;
+
+		reader.skip_whitespace();
+		if reader.peek().is_some() {
+			let location = reader.location();
+			return Err(Error::UnexpectedCharacter(reader.next()?, location));
+		}
+
+		Ok(Box::new(conf))
+	}
+
+	fn item(&mut self, key: &ConfPath) -> &mut StringItem {
+		self.items.entry(key.clone()).or_insert_with(|| StringItem::new(key.clone()))
+	}
+
+	fn parse_object(&mut self, reader: &mut Reader, path: &ConfPath, depth: usize) -> Result<(), Error> {
+		if depth >= MAX_NESTING_DEPTH {
+			return Err(Error::TooDeep(reader.location()));
+		}
+
+		reader.expect('{')?;
+
+		let mut members = HashSet::new();
+
+		reader.skip_whitespace();
+		if reader.peek() == Some('}') {
+			reader.next()?;
+			return Ok(());
+		}
+
+		loop {
+			reader.skip_whitespace();
+			let name = reader.string()?;
+			reader.expect(':')?;
+
+			let key = path.push(&name);
+			if !members.insert(name) {
+				// The last member with the same name wins.
+				self.items.retain(|existing, _| !existing.starts_with(&key));
+			}
+
+			self.parse_value(reader, &key, depth + 1)?;
+
+			reader.skip_whitespace();
+			let location = reader.location();
+			match reader.next()? {
+				',' => (),
+				'}' => return Ok(()),
+				c => return Err(Error::UnexpectedCharacter(c, location))
+			}
+		}
+	}
+
+	/// Parses a value and adds it to the configuration item `key`.
+	fn parse_value(&mut self, reader: &mut Reader, key: &ConfPath, depth: usize) -> Result<(), Error> {
+		reader.skip_whitespace();
+
+		let location = reader.location();
+		let value = match reader.peek() {
+			Some('{') => return self.parse_object(reader, key, depth),
+			Some('[') => return self.parse_array(reader, key, depth),
+			Some('"') => reader.string()?,
+			Some('t') => { reader.keyword("true")?; String::from("true") },
+			Some('f') => { reader.keyword("false")?; String::from("false") },
+			Some('n') => {
+				reader.keyword("null")?;
+				self.item(key);
+				return Ok(());
+			},
+			Some(c) if c == '-' || c.is_ascii_digit() => reader.number()?,
+			Some(c) => return Err(Error::UnexpectedCharacter(c, location)),
+			None => return Err(Error::UnexpectedEnd(location))
+		};
+
+		self.item(key).push(Value::new(value, location));
+
+		Ok(())
+	}
+
+	fn parse_array(&mut self, reader: &mut Reader, key: &ConfPath, depth: usize) -> Result<(), Error> {
+		if depth >= MAX_NESTING_DEPTH {
+			return Err(Error::TooDeep(reader.location()));
+		}
+
+		reader.expect('[')?;
+
+		reader.skip_whitespace();
+		if reader.peek() == Some(']') {
+			reader.next()?;
+
+			// An empty array results in an item without values.
+			self.item(key);
+			return Ok(());
+		}
+
+		for index in 0.. {
+			reader.skip_whitespace();
+			match reader.peek() {
+				Some('{') => self.parse_object(reader, &key.push(&index.to_string()), depth + 1)?,
+				Some('[') => {
+					let location = reader.location();
+					return Err(Error::UnexpectedCharacter('[', location));
+				},
+				_ => self.parse_value(reader, key, depth + 1)?
+			}
+
+			reader.skip_whitespace();
+			let location = reader.location();
+			match reader.next()? {
+				',' => (),
+				']' => break,
+				c => return Err(Error::UnexpectedCharacter(c, location))
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Source for ConfigJson {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn prepare_test_config(json: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigJson::new(json.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	fn values(c: &Config, key: &[&str]) -> Vec<String> {
+		c.get(ConfPath::from(key)).values(..).unwrap()
+	}
+
+	#[test]
+	fn parsing() {
+		let c = prepare_test_config(r#"
+{
+	"string": "a \"quoted\" \\ value \u00e4\ud83d\ude00",
+	"number": -12.5e3,
+	"bool": true,
+	"list": ["a", 1, false],
+	"nested": { "a": { "b": "c" } },
+	"empty": [],
+	"nothing": null,
+	"tables": [ { "name": "first" }, { "name": "second" } ]
+}
+"#);
+
+		assert_eq!(values(&c, &["string"]), ["a \"quoted\" \\ value \u{e4}\u{1f600}"]);
+		assert_eq!(values(&c, &["number"]), ["-12.5e3"]);
+		assert_eq!(values(&c, &["bool"]), ["true"]);
+		assert_eq!(values(&c, &["list"]), ["a", "1", "false"]);
+		assert_eq!(values(&c, &["nested", "a", "b"]), ["c"]);
+		assert!(values(&c, &["empty"]).is_empty());
+		assert!(values(&c, &["nothing"]).is_empty());
+		assert_eq!(values(&c, &["tables", "0", "name"]), ["first"]);
+		assert_eq!(values(&c, &["tables", "1", "name"]), ["second"]);
+	}
+
+	#[test]
+	fn duplicate_members() {
+		let c = prepare_test_config(r#"{ "a": { "b": 1, "c": 2 }, "a": { "b": 3 } }"#);
+
+		assert_eq!(values(&c, &["a", "b"]), ["3"]);
+		assert!(c.get(ConfPath::from(&["a", "c"])).is_err());
+	}
+
+	#[test]
+	fn null_hides_values() {
+		let mut c = Config::default();
+		c.add_source(ConfigJson::new(r#"{ "a": null }"#.as_bytes(), "first").unwrap());
+		c.add_source(ConfigJson::new(r#"{ "a": "value" }"#.as_bytes(), "second").unwrap());
+
+		assert!(values(&c, &["a"]).is_empty());
+	}
+
+	#[test]
+	fn source_location() {
+		let c = prepare_test_config("{\n  \"a\": \"x\",\n  \"b\": [1,\n    2]\n}");
+
+		let error = (c.get(ConfPath::from(&["b"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "More than 1 value found for key b@['json:myfile:3:9', 'json:myfile:4:5']");
+
+		let error = (c.get(ConfPath::from(&["a"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'json:myfile:2:8'");
+	}
+
+	#[test]
+	fn keys() {
+		let c = prepare_test_config(r#"{ "a": 1, "b": { "c": [ { "d": 2 } ] } }"#);
+
+		assert_eq!(c.keys().iter().map(|k| k.to_string()).collect::<Vec<_>>(), ["a", "b.c.0.d"]);
+	}
+
+	#[test]
+	fn object_arrays() {
+		let c = prepare_test_config(r#"{ "servers": [ { "host": "a" }, { "host": "b" } ] }"#);
+
+		assert_eq!(values(&c, &["servers", "0", "host"]), ["a"]);
+		assert_eq!(values(&c, &["servers", "1", "host"]), ["b"]);
+		assert!(c.unused_keys().is_empty());
+	}
+
+	#[test]
+	fn with_path() {
+		let root = ConfPath::default();
+		ConfigJson::with_path(r#"{ "a": { "b": 1 } }"#.as_bytes(), "myfile", &root).unwrap();
+
+		assert_eq!(root.children().map(|c| c.to_string()).collect::<Vec<_>>(), ["a"]);
+	}
+
+	#[test]
+	fn syntax_errors() {
+		fn parse_error(json: &str) -> String {
+			ConfigJson::new(json.as_bytes(), "myfile").err().unwrap().to_string()
+		}
+
+		assert_eq!(parse_error("[1, 2]"), "The document must contain an object in json:myfile:1:1");
+		assert_eq!(parse_error("{ \"a\": 1 \"b\": 2 }"), "Unexpected character '\"' in json:myfile:1:10");
+		assert_eq!(parse_error("{ \"a\": 01 }"), "Invalid number in json:myfile:1:8");
+		assert_eq!(parse_error("{ \"a\": \"\\x\" }"), "Invalid escape sequence in json:myfile:1:9");
+		assert_eq!(parse_error("{ \"a\": [[1]] }"), "Unexpected character '[' in json:myfile:1:9");
+		assert_eq!(parse_error("{ \"a\": tru }"), "Unexpected character ' ' in json:myfile:1:11");
+		assert_eq!(parse_error("{ \"a\": 1 } x"), "Unexpected character 'x' in json:myfile:1:12");
+		assert_eq!(parse_error("{ \"a\": "), "Unexpected end of document in json:myfile:1:8");
+	}
+}
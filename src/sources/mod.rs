@@ -3,4 +3,78 @@
 //! These are the batteries, that are included with just-config.
 pub mod text;
 pub mod defaults;
-pub mod env;
\ No newline at end of file
+pub mod env;
+pub mod json;
+#[cfg(feature = "toml")]
+pub mod toml;
+pub mod position;
+pub mod properties;
+pub mod dotenv;
+pub mod args;
+pub mod dir;
+pub mod systemd_creds;
+pub mod cmdline;
+pub mod exec;
+pub mod mount;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+pub mod reloadable;
+
+use crate::Config;
+use crate::confpath::ConfPath;
+use crate::source::Source;
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::path::Path;
+
+/// Helper function for config file stacking.
+///
+/// This function searches the given list of paths in the supplied order for a
+/// file named `file_name` and adds every file found as a configuration source.
+/// That way the configuration files are merged by the rules stated in
+/// [`add_source`](crate::Config::add_source). Paths that do not contain the
+/// file are skipped.
+///
+/// Every file found is passed to the `loader` together with its name and the
+/// [`ConfPath`] to store the configuration values in. The `with_path`
+/// constructors of the file based sources can be used as the loader. If no
+/// `config_path` is passed, the default root path is used. See
+/// [Enumerating keys](crate#enumerating-keys) for details.
+///
+/// [`text::stack_config`] is a shortcut for stacking text configuration files.
+///
+/// ## Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// # use std::ffi::OsStr;
+/// # use justconfig::Config;
+/// # use justconfig::sources::stack_config;
+/// # use justconfig::sources::json::ConfigJson;
+///
+/// // Define the search path.
+/// let paths: [&Path; 2] = [
+///   &Path::new("/usr/share/myapp/etc"),
+///   &Path::new("/etc")
+/// ];
+///
+/// let mut config = Config::default();
+/// stack_config(&mut config, None, OsStr::new("myapp.json"), &paths[..], ConfigJson::with_path).unwrap();
+/// ```
+pub fn stack_config<S, E, L>(config: &mut Config, config_path: Option<&ConfPath>, file_name: &OsStr, paths: &[&Path], loader: L) -> Result<(), E>
+where
+	S: Source + 'static,
+	L: Fn(File, &str, &ConfPath) -> Result<Box<S>, E>
+{
+	let root = config_path.cloned().unwrap_or_default();
+
+	for &path in paths {
+		let this_path = path.join(file_name);
+		if let Ok(config_file) = File::open(&this_path) {
+			config.add_source(loader(config_file, &this_path.to_string_lossy(), &root)?);
+		}
+	}
+
+	Ok(())
+}
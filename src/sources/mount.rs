@@ -0,0 +1,172 @@
+//! Mount and subtree sources.
+//!
+//! These sources wrap another configuration source and translate the
+//! configuration paths passed to it.
+//!
+//! [`Mount`] places all keys of the wrapped source below a prefix. This allows
+//! the configuration file of a plugin to be read without a section for the
+//! plugin and to make its values available below `plugins.foo` in the merged
+//! configuration.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::text::ConfigText;
+//! use justconfig::sources::mount::Mount;
+//!
+//! let plugin_conf = ConfigText::new("enabled=true\n".as_bytes(), "foo.conf").unwrap();
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(Mount::new(conf.root().push_all(["plugins", "foo"]), plugin_conf));
+//!
+//! let enabled: bool = conf.get(ConfPath::from(&["plugins", "foo", "enabled"])).value().unwrap();
+//! assert!(enabled);
+//! ```
+//!
+//! [`Subtree`] does the opposite. It only exposes the keys of the wrapped
+//! source below a prefix and removes the prefix from them.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::text::ConfigText;
+//! use justconfig::sources::mount::Subtree;
+//!
+//! let shared_conf = ConfigText::new("myapp.port=8080\nother.port=80\n".as_bytes(), "shared.conf").unwrap();
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(Subtree::new(conf.root().push("myapp"), shared_conf));
+//!
+//! let port: u16 = conf.get(ConfPath::from(&["port"])).value().unwrap();
+//! assert_eq!(port, 8080);
+//! assert_eq!(conf.keys(), [ConfPath::from(&["port"])]);
+//! ```
+//!
+//! The values keep the source locations of the wrapped source. Reloading is
+//! passed on to the wrapped source.
+use crate::source::Source;
+use crate::item::StringItem;
+use crate::confpath::ConfPath;
+
+use std::error::Error;
+
+/// Implements the mount source.
+pub struct Mount {
+	prefix: ConfPath,
+	source: Box<dyn Source>
+}
+
+impl Mount {
+	/// Mounts the passed configuration source at `prefix`.
+	///
+	/// The key `a.b` of the wrapped source is available as `prefix.a.b`.
+	/// Keys not starting with `prefix` are unknown to this source.
+	pub fn new(prefix: ConfPath, source: Box<dyn Source>) -> Box<Self> {
+		Box::new(Self {
+			prefix,
+			source
+		})
+	}
+}
+
+impl Source for Mount {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		let inner_key = key.strip_prefix(&self.prefix)?;
+
+		self.source.get(inner_key).map(|item| StringItem::from(key, item.values()))
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.source.keys().iter().map(|key| self.prefix.join(key)).collect()
+	}
+
+	fn reload(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+		self.source.reload()
+	}
+}
+
+/// Implements the subtree source.
+pub struct Subtree {
+	prefix: ConfPath,
+	source: Box<dyn Source>
+}
+
+impl Subtree {
+	/// Exposes the keys of the passed configuration source below `prefix`.
+	///
+	/// The key `prefix.a.b` of the wrapped source is available as `a.b`.
+	/// All other keys of the wrapped source are hidden.
+	pub fn new(prefix: ConfPath, source: Box<dyn Source>) -> Box<Self> {
+		Box::new(Self {
+			prefix,
+			source
+		})
+	}
+}
+
+impl Source for Subtree {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.source.get(self.prefix.join(&key)).map(|item| StringItem::from(key, item.values()))
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.source.keys().iter()
+			.filter_map(|key| key.strip_prefix(&self.prefix))
+			.filter(|key| !key.is_root())
+			.collect()
+	}
+
+	fn reload(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+		self.source.reload()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use crate::sources::text::ConfigText;
+
+	fn text(conf: &str) -> Box<dyn Source> {
+		ConfigText::new(conf.as_bytes(), "test").unwrap()
+	}
+
+	#[test]
+	fn mount() {
+		let mut c = Config::default();
+		c.add_source(Mount::new(ConfPath::from(&["plugins", "foo"]), text("a.b=1\nc=x\n")));
+
+		assert_eq!((c.get(ConfPath::from(&["plugins", "foo", "a", "b"])).value() as Result<u32, ConfigError>).unwrap(), 1);
+		assert!((c.get(ConfPath::from(&["a", "b"])).try_value() as Result<Option<u32>, ConfigError>).unwrap().is_none());
+		assert_eq!(c.keys(), [ConfPath::from(&["plugins", "foo", "a", "b"]), ConfPath::from(&["plugins", "foo", "c"])]);
+
+		// Errors show the mounted path and the original source location.
+		let error = (c.get(ConfPath::from(&["plugins", "foo", "c"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'conf:test:2'");
+		assert_eq!(c.get(ConfPath::from(&["plugins", "foo", "c"])).unwrap().key(), &ConfPath::from(&["plugins", "foo", "c"]));
+	}
+
+	#[test]
+	fn subtree() {
+		let mut c = Config::default();
+		c.add_source(Subtree::new(ConfPath::from(&["myapp"]), text("myapp=root\nmyapp.a.b=1\nother.c=2\n")));
+
+		assert_eq!((c.get(ConfPath::from(&["a", "b"])).value() as Result<u32, ConfigError>).unwrap(), 1);
+		assert!((c.get(ConfPath::from(&["other", "c"])).try_value() as Result<Option<u32>, ConfigError>).unwrap().is_none());
+		assert_eq!(c.keys(), [ConfPath::from(&["a", "b"])]);
+		assert_eq!(c.get(ConfPath::from(&["a", "b"])).unwrap().key(), &ConfPath::from(&["a", "b"]));
+	}
+
+	#[test]
+	fn nested() {
+		let mut c = Config::default();
+		c.add_source(Mount::new(ConfPath::from(&["x"]), Subtree::new(ConfPath::from(&["y"]), text("y.k=v\n"))));
+
+		assert_eq!((c.get(ConfPath::from(&["x", "k"])).value() as Result<String, ConfigError>).unwrap(), "v");
+		assert_eq!(c.keys(), [ConfPath::from(&["x", "k"])]);
+	}
+}
@@ -0,0 +1,96 @@
+//! Source locations of document based sources.
+//!
+//! The document based sources ([`json`](super::json), `toml` and `yaml`)
+//! share the [`PositionSourceLocation`] type that points to the line and the
+//! column a value starts at.
+use crate::item::SourceLocation;
+
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+use std::fmt;
+
+/// Source location pointing to a line and a column within a document.
+/// This value is used to store the source of every configuration value for
+/// use in error messages.
+///
+/// The location is displayed as `format:name:line:column`. The format is the
+/// name of the document format like `json`, `toml` or `yaml`.
+#[derive(Debug)]
+pub struct PositionSourceLocation {
+	format: &'static str,
+	source_name: String,
+	line: usize,
+	column: usize
+}
+
+impl PositionSourceLocation {
+	pub(crate) fn new(format: &'static str, source_name: &str, line: usize, column: usize) -> Arc<Self> {
+		Arc::new(Self {
+			format,
+			source_name: source_name.to_owned(),
+			line,
+			column
+		})
+	}
+}
+
+impl fmt::Display for PositionSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}:{}:{}", self.format, self.source_name, self.line, self.column)
+	}
+}
+
+impl SourceLocation for PositionSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.source_name)
+	}
+
+	fn line(&self) -> Option<usize> {
+		Some(self.line)
+	}
+}
+
+/// Character stream that keeps track of the current position.
+pub(crate) struct Reader<'a> {
+	chars: Peekable<Chars<'a>>,
+	format: &'static str,
+	source_name: &'a str,
+	line: usize,
+	column: usize
+}
+
+impl <'a> Reader<'a> {
+	pub(crate) fn new(document: &'a str, format: &'static str, source_name: &'a str) -> Self {
+		Self {
+			chars: document.chars().peekable(),
+			format,
+			source_name,
+			line: 1,
+			column: 1
+		}
+	}
+
+	/// Returns the location of the next character.
+	pub(crate) fn location(&self) -> Arc<PositionSourceLocation> {
+		PositionSourceLocation::new(self.format, self.source_name, self.line, self.column)
+	}
+
+	pub(crate) fn peek(&mut self) -> Option<char> {
+		self.chars.peek().copied()
+	}
+
+	/// Consumes the next character. Returns `None` at the end of the document.
+	pub(crate) fn next(&mut self) -> Option<char> {
+		let c = self.chars.next()?;
+
+		if c == '\n' {
+			self.line += 1;
+			self.column = 1;
+		} else {
+			self.column += 1;
+		}
+
+		Some(c)
+	}
+}
@@ -0,0 +1,372 @@
+//! Properties source.
+//!
+//! This source parses Java-style `.properties` files into configuration
+//! information. This allows the configuration of legacy services to be read
+//! without converting it first.
+//!
+//! Any struct that implements `Read` can be used as a source for configuration
+//! values. The file must be UTF-8 encoded.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::properties::ConfigProperties;
+//!
+//! let properties = r#"
+//! ## Server settings
+//! server.port = 8080
+//! server.name : J\u00fcrgen's server
+//! server.hosts = first, \
+//!                second
+//! "#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigProperties::new(properties.as_bytes(), "myconfig.properties").unwrap());
+//!
+//! let port: u16 = conf.get(conf.root().push_all(["server", "port"])).value().unwrap();
+//! let name: String = conf.get(conf.root().push_all(["server", "name"])).value().unwrap();
+//! let hosts: String = conf.get(conf.root().push_all(["server", "hosts"])).value().unwrap();
+//!
+//! assert_eq!(port, 8080);
+//! assert_eq!(name, "Jürgen's server");
+//! assert_eq!(hosts, "first, second");
+//! ```
+//!
+//! ## Format
+//!
+//! The format follows the rules of `java.util.Properties`:
+//!
+//! * Lines starting with `#` or `!` are comments.
+//! * The key is separated from the value by `=`, `:` or white-space. White-space
+//!   around the separator is ignored.
+//! * A line ending with a backslash (`\`) is continued on the next line. The
+//!   leading white-space of the continuation line is removed.
+//! * The escape sequences `\t`, `\n`, `\r`, `\f` and `\uXXXX` are supported
+//!   within keys and values. Characters outside the basic multilingual plane
+//!   are written as a UTF-16 surrogate pair (`\uD83D\uDE00`). A backslash
+//!   followed by any other character results in that character. This way
+//!   `\=`, `\:` and `\ ` can be used within keys.
+//! * If a key is set multiple times the last value wins.
+//!
+//! The key is split at every dot (`.`) to form the components of the
+//! configuration path. `server.port` is stored as `server` → `port`.
+//!
+//! The source locations of the values contain the name of the source and the
+//! lines the property was read from.
+use crate::source::Source;
+use crate::item::{SourceLocation, StringItem, Value};
+use crate::confpath::ConfPath;
+
+use std::io::{Read, BufRead, BufReader};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::fmt;
+
+/// Enumeration containing parse errors.
+#[derive(Debug)]
+pub enum Error {
+	/// A `\u` escape sequence is not followed by four hexadecimal digits or a
+	/// UTF-16 surrogate is not part of a valid surrogate pair.
+	InvalidEscape(Arc<PropertiesSourceLocation>),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::InvalidEscape(location) => write!(f, "Invalid unicode escape sequence in {}", location),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Source location for the ConfigProperties configuration source.
+/// This value is used to store the source of every configuration value for
+/// use in error messages.
+#[derive(Debug)]
+pub struct PropertiesSourceLocation {
+	source_name: String,
+	line_start: usize,
+	line_end: usize
+}
+
+impl PropertiesSourceLocation {
+	fn new(source_name: &str, line_start: usize, line_end: usize) -> Arc<Self> {
+		Arc::new(Self {
+			source_name: source_name.to_owned(),
+			line_start,
+			line_end
+		})
+	}
+}
+
+impl fmt::Display for PropertiesSourceLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.line_start == self.line_end {
+			write!(f, "properties:{}:{}", self.source_name, self.line_start)
+		} else {
+			write!(f, "properties:{}:{}-{}", self.source_name, self.line_start, self.line_end)
+		}
+	}
+}
+
+impl SourceLocation for PropertiesSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.source_name)
+	}
+
+	fn line(&self) -> Option<usize> {
+		Some(self.line_start)
+	}
+}
+
+/// Implements the properties source.
+pub struct ConfigProperties {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigProperties {
+	/// Parses a properties file into configuration information.
+	///
+	/// Any instance of a struct implementing `Read` can be passed to the parser.
+	/// As the second parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	///
+	/// The method returns a new `ConfigProperties` instance or an error if the
+	/// file could not be parsed.
+	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(conf_source, source_name, &ConfPath::default())
+	}
+
+	/// Parses a properties file and fills a `ConfPath` with the contained keys.
+	///
+	/// This method works like [`ConfigText::with_path`](super::text::ConfigText::with_path).
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the file.
+	pub fn with_path(conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+
+		let mut lines = (1usize..).zip(BufReader::new(conf_source).lines());
+		while let Some((line_start, read_line)) = lines.next() {
+			let line = read_line?;
+
+			let trimmed = line.trim_start();
+			if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+				continue;
+			}
+
+			// Join continuation lines. The leading white-space of every continuation line is removed.
+			let mut logical_line = trimmed.to_owned();
+			let mut line_end = line_start;
+			while Self::is_continued(&logical_line) {
+				logical_line.pop();
+
+				match lines.next() {
+					Some((line_no, read_line)) => {
+						logical_line.push_str(read_line?.trim_start());
+						line_end = line_no;
+					},
+					None => break
+				}
+			}
+
+			let location = PropertiesSourceLocation::new(source_name, line_start, line_end);
+			let (key, value) = Self::split(&logical_line);
+			let key = Self::unescape(key, &location)?;
+			let value = Self::unescape(value, &location)?;
+
+			let path = path_root.push_all(key.split('.'));
+			let mut item = StringItem::new(path.clone());
+			item.push(Value::new(value, location));
+
+			// Like in java.util.Properties the last value wins.
+			conf.items.insert(path, item);
+		}
+
+		Ok(Box::new(conf))
+	}
+
+	/// Returns true if the line ends with an odd number of backslashes.
+	fn is_continued(line: &str) -> bool {
+		line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+	}
+
+	/// Splits a logical line into the still escaped key and value.
+	fn split(line: &str) -> (&str, &str) {
+		let mut chars = line.char_indices();
+
+		while let Some((pos, c)) = chars.next() {
+			match c {
+				'\\' => { chars.next(); },
+				'=' | ':' => return (&line[..pos], line[pos + 1..].trim_start()),
+				c if c.is_whitespace() => {
+					// White-space can be followed by an additional separator.
+					let value = line[pos..].trim_start();
+					let value = value.strip_prefix(['=', ':']).map_or(value, str::trim_start);
+					return (&line[..pos], value);
+				},
+				_ => ()
+			}
+		}
+
+		(line, "")
+	}
+
+	/// Reads the four hexadecimal digits of a `\u` escape sequence.
+	fn hex_escape(chars: &mut std::str::Chars) -> Option<u32> {
+		let mut code_point = 0;
+		for _ in 0..4 {
+			code_point = code_point * 16 + chars.next()?.to_digit(16)?;
+		}
+
+		Some(code_point)
+	}
+
+	fn unescape(s: &str, location: &Arc<PropertiesSourceLocation>) -> Result<String, Error> {
+		let mut result = String::with_capacity(s.len());
+		let mut chars = s.chars();
+
+		while let Some(c) = chars.next() {
+			if c != '\\' {
+				result.push(c);
+				continue;
+			}
+
+			match chars.next() {
+				Some('t') => result.push('\t'),
+				Some('n') => result.push('\n'),
+				Some('r') => result.push('\r'),
+				Some('f') => result.push('\u{c}'),
+				Some('u') => {
+					let mut code_point = Self::hex_escape(&mut chars).ok_or_else(|| Error::InvalidEscape(location.clone()))?;
+
+					if (0xD800..0xDC00).contains(&code_point) {
+						// UTF-16 surrogate pair
+						let low = match (chars.next(), chars.next()) {
+							(Some('\\'), Some('u')) => Self::hex_escape(&mut chars).filter(|low| (0xDC00..0xE000).contains(low)),
+							_ => None
+						}.ok_or_else(|| Error::InvalidEscape(location.clone()))?;
+
+						code_point = 0x10000 + ((code_point - 0xD800) << 10) + (low - 0xDC00);
+					}
+
+					result.push(std::char::from_u32(code_point).ok_or_else(|| Error::InvalidEscape(location.clone()))?);
+				},
+				Some(c) => result.push(c),
+				None => ()
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+impl Source for ConfigProperties {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn prepare_test_config(properties: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigProperties::new(properties.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	fn value(c: &Config, key: &[&str]) -> String {
+		c.get(ConfPath::from(key)).value().unwrap()
+	}
+
+	#[test]
+	fn parsing() {
+		let c = prepare_test_config(r#"
+# Comment
+! Another comment
+equals=value1
+colon : value2
+space   value3
+  indented = value4
+escaped\ key\=x = a\tb\\c
+unicode = \u00e4\u00F6
+continued = first \
+    second \
+	third
+backslash = c:\\
+empty
+dotted.key.name = value5
+dotted.key.name = value6
+"#);
+
+		assert_eq!(value(&c, &["equals"]), "value1");
+		assert_eq!(value(&c, &["colon"]), "value2");
+		assert_eq!(value(&c, &["space"]), "value3");
+		assert_eq!(value(&c, &["indented"]), "value4");
+		assert_eq!(value(&c, &["escaped key=x"]), "a\tb\\c");
+		assert_eq!(value(&c, &["unicode"]), "\u{e4}\u{f6}");
+		assert_eq!(value(&c, &["continued"]), "first second third");
+		assert_eq!(value(&c, &["backslash"]), "c:\\");
+		assert_eq!(value(&c, &["empty"]), "");
+		assert_eq!(value(&c, &["dotted", "key", "name"]), "value6");
+	}
+
+	#[test]
+	fn source_location() {
+		let c = prepare_test_config("a = x\nb = 1, \\\n  2\n");
+
+		let error = (c.get(ConfPath::from(&["a"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'properties:myfile:1'");
+
+		let error = (c.get(ConfPath::from(&["b"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'properties:myfile:2-3'");
+	}
+
+	#[test]
+	fn invalid_escape() {
+		match ConfigProperties::new("a = \\u00g1".as_bytes(), "myfile") {
+			Err(Error::InvalidEscape(location)) => assert_eq!(location.to_string(), "properties:myfile:1"),
+			_ => panic!("Invalid escape sequence not detected.")
+		}
+
+		for properties in ["a = \\u+abc", "a = \\uD83D", "a = \\uD83Dx", "a = \\uD83D\\u0041", "a = \\uDE00"] {
+			assert!(matches!(ConfigProperties::new(properties.as_bytes(), "myfile"), Err(Error::InvalidEscape(_))), "Invalid escape sequence not detected in {}.", properties);
+		}
+	}
+
+	#[test]
+	fn surrogate_pair() {
+		let c = prepare_test_config("a = \\uD83D\\uDE00 \\ud83d\\ude00\n");
+
+		assert_eq!(value(&c, &["a"]), "\u{1F600} \u{1F600}");
+	}
+}
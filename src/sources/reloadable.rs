@@ -0,0 +1,231 @@
+//! Reloadable source
+//!
+//! The `Reloadable` source wraps another configuration source that is read
+//! from one or more files. Every time [`reload`](crate::Config::reload) is
+//! called, it checks if the modification time or the size of the watched files
+//! changed. If they did, the wrapped configuration source is recreated by
+//! calling a loader function.
+//!
+//! The new configuration source replaces the old one only if the loader
+//! function succeeds. If the configuration file contains an error, the old
+//! configuration stays active and the error is returned by
+//! [`reload`](crate::Config::reload). The next call to `reload` retries
+//! loading the configuration files.
+//!
+//! For text configuration files the convenience method
+//! [`text_file`](Reloadable::text_file) is provided. Other configuration sources
+//! can be wrapped by calling [`new`](Reloadable::new) with a custom loader.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::source::Source;
+//! use justconfig::sources::text::ConfigText;
+//! use justconfig::sources::reloadable::Reloadable;
+//! use std::fs::File;
+//! use std::path::PathBuf;
+//! # let config_file = std::env::temp_dir().join(format!("justconfig-reloadable-doc-{}.conf", std::process::id()));
+//! # std::fs::write(&config_file, "key=value\n").unwrap();
+//!
+//! let mut conf = Config::default();
+//!
+//! let path = config_file.clone();
+//! conf.add_source(Reloadable::new(&[&config_file], move || {
+//!   let source: Box<dyn Source> = ConfigText::new(File::open(&path)?, &path.to_string_lossy())?;
+//!   Ok(source)
+//! }).unwrap());
+//!
+//! // Periodically check for changes
+//! conf.reload().unwrap();
+//! # std::fs::remove_file(&config_file).unwrap();
+//! ```
+use crate::source::Source;
+use crate::item::StringItem;
+use crate::confpath::ConfPath;
+use crate::sources::text::ConfigText;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::sync::{Mutex, RwLock, PoisonError};
+use std::error::Error;
+use std::fs::{self, File};
+
+/// Function creating the wrapped configuration source.
+type Loader = dyn Fn() -> Result<Box<dyn Source>, Box<dyn Error + Send + Sync>> + Send + Sync;
+
+/// State of a watched file. `None` if the file does not exist.
+type FileState = Option<(Option<SystemTime>, u64)>;
+
+/// Implements the reloadable source.
+pub struct Reloadable {
+	loader: Box<Loader>,
+	paths: Vec<PathBuf>,
+	file_states: Mutex<Vec<FileState>>,
+	source: RwLock<Box<dyn Source>>
+}
+
+impl Reloadable {
+	/// Creates a new reloadable source.
+	///
+	/// The `files` parameter contains the paths of all files that should be
+	/// watched for changes. The `loader` function is called to create the
+	/// wrapped configuration source. It is called once by this method and
+	/// every time one of the watched files changes.
+	///
+	/// If the loader function returns an error, it is returned by this method.
+	///
+	/// See the [`reloadable`](mod@super::reloadable) module for more information.
+	pub fn new<F>(files: &[&Path], loader: F) -> Result<Box<Self>, Box<dyn Error + Send + Sync>> where F: Fn() -> Result<Box<dyn Source>, Box<dyn Error + Send + Sync>> + Send + Sync + 'static {
+		// Determine the state of the files before loading them to not miss a
+		// change while the loader is running.
+		let file_states = files.iter().map(|path| Self::file_state(path)).collect();
+		let source = loader()?;
+
+		Ok(Box::new(Self {
+			loader: Box::new(loader),
+			paths: files.iter().map(|path| path.to_path_buf()).collect(),
+			file_states: Mutex::new(file_states),
+			source: RwLock::new(source)
+		}))
+	}
+
+	/// Creates a reloadable source for a text configuration file.
+	///
+	/// The file is parsed by [`ConfigText`]. The path of the file is used as
+	/// the name of the configuration source.
+	pub fn text_file(path: &Path) -> Result<Box<Self>, Box<dyn Error + Send + Sync>> {
+		let loader_path = path.to_path_buf();
+
+		Self::new(&[path], move || {
+			let source: Box<dyn Source> = ConfigText::new(File::open(&loader_path)?, &loader_path.to_string_lossy())?;
+			Ok(source)
+		})
+	}
+
+	fn file_state(path: &Path) -> FileState {
+		fs::metadata(path).ok().map(|metadata| (metadata.modified().ok(), metadata.len()))
+	}
+}
+
+impl Source for Reloadable {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.source.read().unwrap_or_else(PoisonError::into_inner).get(key)
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.source.read().unwrap_or_else(PoisonError::into_inner).keys()
+	}
+
+	fn reload(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+		// Holding the lock makes sure that concurrent calls do not load the
+		// same change twice.
+		let mut file_states = self.file_states.lock().unwrap_or_else(PoisonError::into_inner);
+		let new_states: Vec<FileState> = self.paths.iter().map(|path| Self::file_state(path)).collect();
+
+		if *file_states == new_states {
+			// The files did not change. Give the wrapped source the chance to reload itself.
+			return self.source.read().unwrap_or_else(PoisonError::into_inner).reload();
+		}
+
+		// Only replace the source if the new configuration could be loaded.
+		let new_source = (self.loader)()?;
+		*self.source.write().unwrap_or_else(PoisonError::into_inner) = new_source;
+		*file_states = new_states;
+
+		Ok(true)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::reload::Change;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use std::sync::Arc;
+
+	fn temp_file(name: &str, content: &str) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("justconfig-reloadable-{}-{}.conf", std::process::id(), name));
+		fs::write(&path, content).unwrap();
+
+		path
+	}
+
+	#[test]
+	fn reload() {
+		let path = temp_file("reload", "key1=value1\nkey2=value2\n");
+
+		let mut c = Config::default();
+		c.add_source(Reloadable::text_file(&path).unwrap());
+
+		let reported = Arc::new(Mutex::new(Vec::default()));
+		let listener_reported = reported.clone();
+		c.on_change(move |changes| listener_reported.lock().unwrap().extend(changes.iter().map(|change| change.to_string())));
+
+		// Nothing changed
+		assert!(c.reload().unwrap().is_empty());
+		assert!(reported.lock().unwrap().is_empty());
+
+		fs::write(&path, "key1=value1.new\nkey3=value3\nkey3=value3.2\n").unwrap();
+
+		let changes = c.reload().unwrap();
+		assert_eq!(changes.len(), 3);
+		assert!(matches!(&changes[0], Change::Modified(_, _)));
+		assert_eq!(changes[0].old_item().unwrap().values()[0].value(), "value1");
+		assert_eq!(changes[0].new_item().unwrap().values()[0].value(), "value1.new");
+		assert!(matches!(&changes[1], Change::Removed(_)));
+		assert_eq!(*changes[1].key(), ConfPath::from(&["key2"]));
+		assert!(matches!(&changes[2], Change::Added(_)));
+
+		assert_eq!(*reported.lock().unwrap(), [
+			"Modified config key 'key1': ['value1'] -> ['value1.new']",
+			"Removed config key 'key2': ['value2']",
+			"Added config key 'key3': ['value3', 'value3.2']"
+		]);
+
+		assert_eq!((c.get(ConfPath::from(&["key1"])).value() as Result<String, ConfigError>).unwrap(), "value1.new");
+
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn reload_error() {
+		let path = temp_file("error", "key=value\n");
+
+		let mut c = Config::default();
+		c.add_source(Reloadable::text_file(&path).unwrap());
+
+		// Write an invalid configuration file. The old values must be kept.
+		fs::write(&path, "key=value.new\ninvalid line\n").unwrap();
+
+		match c.reload() {
+			Err(ConfigError::ReloadFailed(_)) => (),
+			_ => panic!("Reload error not reported.")
+		}
+		assert_eq!((c.get(ConfPath::from(&["key"])).value() as Result<String, ConfigError>).unwrap(), "value");
+
+		// Fix the configuration file. Now the new value is loaded.
+		fs::write(&path, "key=value.fixed\n").unwrap();
+
+		assert_eq!(c.reload().unwrap().len(), 1);
+		assert_eq!((c.get(ConfPath::from(&["key"])).value() as Result<String, ConfigError>).unwrap(), "value.fixed");
+
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn unchanged_values() {
+		let path = temp_file("unchanged", "key=value\n");
+
+		let mut c = Config::default();
+		c.add_source(Reloadable::text_file(&path).unwrap());
+
+		// The file changes but the values stay the same.
+		fs::write(&path, "# Comment\nkey=value\n").unwrap();
+
+		assert!(c.reload().unwrap().is_empty());
+
+		fs::remove_file(&path).unwrap();
+	}
+}
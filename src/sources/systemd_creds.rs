@@ -0,0 +1,278 @@
+//! systemd credentials source.
+//!
+//! systemd passes credentials configured with `LoadCredential=`,
+//! `SetCredential=` and similar settings to a service by placing one file per
+//! credential within a directory. The path of this directory is stored within
+//! the environment variable `CREDENTIALS_DIRECTORY`. This source reads the
+//! credentials from this directory.
+//!
+//! By default the name of every credential is split at the dots (`.`) to form
+//! the configuration path. The credential `db.password` is stored as `db` →
+//! `password`. To place the credentials below a common root, pass it to
+//! [`with_path`](SystemdCreds::with_path). Alternatively a mapping between
+//! configuration paths and credential names can be passed to
+//! [`with_mapping`](SystemdCreds::with_mapping). Credentials that do not
+//! exist are treated like unset configuration items.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::ConfPath;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::systemd_creds::SystemdCreds;
+//! # let credentials = std::env::temp_dir().join(format!("justconfig-creds-doc-{}", std::process::id()));
+//! # std::fs::create_dir_all(&credentials).unwrap();
+//! # std::fs::write(credentials.join("db.password"), "secret").unwrap();
+//! # std::env::set_var("CREDENTIALS_DIRECTORY", &credentials);
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(SystemdCreds::new().unwrap());
+//!
+//! let password: String = conf.get(ConfPath::from(&["db", "password"])).value().unwrap();
+//! assert_eq!(password, "secret");
+//! # std::fs::remove_dir_all(&credentials).unwrap();
+//! ```
+//!
+//! The content of the credentials is read like the files of the
+//! [`dir`](super::dir) source by using the default [`DirOptions`]. The source
+//! location of every value contains the path of the credential file.
+use crate::source::Source;
+use crate::item::{StringItem, Value};
+use crate::confpath::ConfPath;
+use crate::sources::dir::{self, DirOptions};
+
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::fmt;
+
+/// Name of the environment variable containing the credentials directory.
+const CREDENTIALS_DIRECTORY: &str = "CREDENTIALS_DIRECTORY";
+
+/// Enumeration containing errors that occur while reading the credentials.
+#[derive(Debug)]
+pub enum Error {
+	/// The environment variable `CREDENTIALS_DIRECTORY` is not set. The
+	/// service was not started by systemd or no credentials are configured.
+	NotSet,
+	/// The name of a credential is not valid unicode.
+	NotUnicode(PathBuf),
+	/// A credential could not be read.
+	Read(dir::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::NotSet => write!(f, "The environment variable {} is not set", CREDENTIALS_DIRECTORY),
+			Error::NotUnicode(path) => write!(f, "The name of the credential '{}' is not valid unicode", path.display()),
+			Error::Read(error) => write!(f, "Reading credential failed: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Read(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<dir::Error> for Error {
+	fn from(dir_error: dir::Error) -> Self {
+		Error::Read(dir_error)
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::Read(dir::Error::IoError(io_error))
+	}
+}
+
+/// Implements the systemd credentials source.
+pub struct SystemdCreds {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl SystemdCreds {
+	/// Reads all credentials from the directory referenced by
+	/// `CREDENTIALS_DIRECTORY`.
+	///
+	/// The name of every credential is split at the dots to form the
+	/// configuration path. Returns [`Error::NotSet`] if the environment
+	/// variable is not set.
+	pub fn new() -> Result<Box<Self>, Error> {
+		Self::with_path(&ConfPath::default())
+	}
+
+	/// Reads all credentials from the directory referenced by
+	/// `CREDENTIALS_DIRECTORY`.
+	///
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while reading the credentials.
+	pub fn with_path(path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		Self::from_dir_with_path(Self::credentials_directory()?, path_root)
+	}
+
+	/// Reads the credentials named within the mapping from the directory
+	/// referenced by `CREDENTIALS_DIRECTORY`.
+	///
+	/// The first element of every tuple defines the configuration path and the
+	/// second element defines the name of the credential. Credentials not
+	/// present within this mapping are inaccessible by the configuration system.
+	/// Returns [`Error::NotSet`] if the environment variable is not set.
+	pub fn with_mapping(mapping: &[(ConfPath, &str)]) -> Result<Box<Self>, Error> {
+		Self::from_dir_with_mapping(Self::credentials_directory()?, mapping)
+	}
+
+	/// Reads all credentials from the passed directory.
+	///
+	/// This method works like [`new`](Self::new) but does not use the
+	/// environment variable `CREDENTIALS_DIRECTORY`.
+	pub fn from_dir(dir: impl AsRef<Path>) -> Result<Box<Self>, Error> {
+		Self::from_dir_with_path(dir, &ConfPath::default())
+	}
+
+	/// Reads all credentials from the passed directory.
+	///
+	/// This method works like [`with_path`](Self::with_path) but does not use
+	/// the environment variable `CREDENTIALS_DIRECTORY`.
+	pub fn from_dir_with_path(dir: impl AsRef<Path>, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		let mut creds = Self {
+			items: HashMap::default()
+		};
+
+		let options = DirOptions::default();
+		for entry in fs::read_dir(dir)? {
+			let path = entry?.path();
+			let name = path.file_name().unwrap().to_str().ok_or_else(|| Error::NotUnicode(path.clone()))?;	// Entries returned by read_dir always have a file name.
+
+			if name.starts_with('.') || name.split('.').any(str::is_empty) || !path.is_file() {
+				continue;
+			}
+
+			creds.insert(path_root.push_all(name.split('.')), options.read_value(&path)?);
+		}
+
+		Ok(Box::new(creds))
+	}
+
+	/// Reads the credentials named within the mapping from the passed
+	/// directory.
+	///
+	/// This method works like [`with_mapping`](Self::with_mapping) but does not
+	/// use the environment variable `CREDENTIALS_DIRECTORY`.
+	pub fn from_dir_with_mapping(dir: impl AsRef<Path>, mapping: &[(ConfPath, &str)]) -> Result<Box<Self>, Error> {
+		let mut creds = Self {
+			items: HashMap::default()
+		};
+
+		let options = DirOptions::default();
+		for (key, name) in mapping {
+			let path = dir.as_ref().join(name);
+			if path.is_file() {
+				creds.insert(key.clone(), options.read_value(&path)?);
+			}
+		}
+
+		Ok(Box::new(creds))
+	}
+
+	fn credentials_directory() -> Result<PathBuf, Error> {
+		env::var_os(CREDENTIALS_DIRECTORY).filter(|dir| !dir.is_empty()).map(PathBuf::from).ok_or(Error::NotSet)
+	}
+
+	fn insert(&mut self, key: ConfPath, value: Arc<Value<String>>) {
+		self.items.insert(key.clone(), StringItem::from(key, &[value]));
+	}
+}
+
+impl Source for SystemdCreds {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn temp_dir(name: &str) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("justconfig-creds-{}-{}", std::process::id(), name));
+		let _ = fs::remove_dir_all(&path);
+		fs::create_dir_all(&path).unwrap();
+		fs::write(path.join("db.password"), "secret\n").unwrap();
+		fs::write(path.join("api_token"), "token").unwrap();
+
+		path
+	}
+
+	#[test]
+	fn naming_convention() {
+		let dir = temp_dir("convention");
+
+		let mut c = Config::default();
+		c.add_source(SystemdCreds::from_dir(&dir).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["db", "password"])).value() as Result<String, ConfigError>).unwrap(), "secret");
+		assert_eq!((c.get(ConfPath::from(&["api_token"])).value() as Result<String, ConfigError>).unwrap(), "token");
+		assert_eq!(c.keys(), [ConfPath::from(&["api_token"]), ConfPath::from(&["db", "password"])]);
+
+		let error = (c.get(ConfPath::from(&["api_token"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), format!("invalid digit found in string@'file:{}'", dir.join("api_token").display()));
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn path_root() {
+		let dir = temp_dir("path_root");
+
+		let mut c = Config::default();
+		c.add_source(SystemdCreds::from_dir_with_path(&dir, &ConfPath::from(&["creds"])).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["creds", "db", "password"])).value() as Result<String, ConfigError>).unwrap(), "secret");
+		assert_eq!(c.keys(), [ConfPath::from(&["creds", "api_token"]), ConfPath::from(&["creds", "db", "password"])]);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn mapping() {
+		let dir = temp_dir("mapping");
+
+		let mut c = Config::default();
+		c.add_source(SystemdCreds::from_dir_with_mapping(&dir, &[
+			(ConfPath::from(&["database", "password"]), "db.password"),
+			(ConfPath::from(&["missing"]), "missing")
+		]).unwrap());
+
+		assert_eq!((c.get(ConfPath::from(&["database", "password"])).value() as Result<String, ConfigError>).unwrap(), "secret");
+		assert!((c.get(ConfPath::from(&["api_token"])).try_value() as Result<Option<String>, ConfigError>).unwrap().is_none());
+		assert!((c.get(ConfPath::from(&["missing"])).try_value() as Result<Option<String>, ConfigError>).unwrap().is_none());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn not_set() {
+		env::remove_var(CREDENTIALS_DIRECTORY);
+		assert!(matches!(SystemdCreds::new(), Err(Error::NotSet)));
+	}
+
+	#[test]
+	fn missing_directory() {
+		assert!(matches!(SystemdCreds::from_dir(std::env::temp_dir().join("justconfig-creds-does-not-exist")), Err(Error::Read(dir::Error::IoError(_)))));
+	}
+}
@@ -153,28 +153,116 @@
 //! 
 //! The second line is appended to the first line after a newline character (`\n`).
 //! 
+//! ## Directives
+//! 
+//! Lines where the first, non white-space character is an exclamation mark (`!`)
+//! followed by the name of a known directive are directives. A directive consists
+//! of its name and an argument separated by white-space. Directives reset the
+//! current key like an empty line does. All other lines starting with an
+//! exclamation mark are parsed as ordinary key-value pairs.
+//! 
+//! ### Removing values
+//! 
+//! If multiple configuration files are stacked (see [`stack_config`]) a
+//! configuration file can not only override but also remove the values set by
+//! configuration files with a lower priority. The `!unset` directive marks a key
+//! as having no values. If the configuration system asks for this key, an empty
+//! item is returned and the search for other configuration sources stops.
+//! 
+//! ```conf
+//! [section]
+//! !unset key
+//! # section.key has no values
+//! 
+//! !unset [other.section]
+//! # All keys within other.section have no values
+//! ```
+//! 
+//! Like normal keys, the key passed to `!unset` is relative to the current section.
+//! To remove a whole section its name is put into square brackets. The section name
+//! is not relative to the current section.
+//! 
+//! Keys set after the `!unset` directive get their values as usual. That way the
+//! values of a key or a section can be replaced instead of being merged with the
+//! values of other configuration sources.
+//! 
+//! ### Including files
+//! 
+//! Including files is disabled by default. Use [`ConfigText::with_includes`] to
+//! enable the `!include` and `!include-dir` directives. If includes are disabled,
+//! lines using these directives are parsed as ordinary key-value pairs.
+//! 
+//! The `!include` directive parses another configuration file and adds its values
+//! to the current configuration source. The `!include-dir` directive includes all
+//! files within a directory that end with `.conf`. Hidden files (starting with a
+//! dot) are ignored. The files are included in lexical order of their names. This
+//! makes it easy to split a configuration into a main file and drop-in fragments:
+//! 
+//! ```conf
+//! key=value
+//! !include common.conf
+//! !include-dir myapp.conf.d
+//! ```
+//! 
+//! Relative paths are resolved relative to the directory of the including file.
+//! For this to work the name of the configuration source (see [`ConfigText::new`])
+//! must be the path of the configuration file. [`stack_config`] does this
+//! automatically.
+//! 
+//! Included files always start without a section. The section of the including
+//! file is not changed by an include. Values of keys that are set in multiple
+//! files are added to the values of the key just like multiple values within one
+//! file. To replace the values of a key within an included file use the `!unset`
+//! directive before setting the new values.
+//! 
+//! The source locations of included values refer to the included file. Includes
+//! can be nested up to a depth of 16 files. Including a file that is already
+//! being parsed results in an [`IncludeCycle`](Error::IncludeCycle) error.
+//! 
+//! Included files are not watched by the
+//! [`Reloadable`](super::reloadable::Reloadable) source. Only changes to the files
+//! passed to [`Reloadable::new`](super::reloadable::Reloadable::new) trigger a
+//! reload.
+//! 
 use crate::source::Source;
 use crate::item::{SourceLocation, StringItem, Value};
 use crate::confpath::ConfPath;
 use crate::Config;
 
 use std::io::{Read, BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs::File;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::fmt;
 
+/// Maximum nesting depth of included files.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Extension of the files included by the `!include-dir` directive.
+const INCLUDE_DIR_EXTENSION: &str = "conf";
+
 /// Enumeration containing parse errors.
 #[derive(Debug)]
 pub enum Error {
 	/// The first none white-space character on the line was an equals sign (`=`)
 	/// but there was no previous line that set the key.
-	NoPreviousKey(Rc<TextSourceLocation>),
+	NoPreviousKey(Arc<TextSourceLocation>),
 	/// A line was found that is not a section header and not a continuation of the
 	/// previous line but misses the key-value-delimiter (`=`).
-	MissingKeyValueDelimiter(Rc<TextSourceLocation>),
+	MissingKeyValueDelimiter(Arc<TextSourceLocation>),
+	/// A directive is missing its argument. The first parameter contains the
+	/// name of the directive.
+	MissingDirectiveArgument(String, Arc<TextSourceLocation>),
+	/// A file or directory passed to the `!include` or `!include-dir` directive
+	/// does not exist or could not be opened.
+	IncludeNotFound(PathBuf, Arc<TextSourceLocation>),
+	/// A file includes itself directly or via other included files.
+	IncludeCycle(PathBuf, Arc<TextSourceLocation>),
+	/// Includes are nested deeper than the maximum include depth of
+	/// 16 files.
+	IncludeTooDeep(PathBuf, Arc<TextSourceLocation>),
 	/// An I/O error occurred while reading.
 	IoError(std::io::Error),
 }
@@ -184,6 +272,10 @@ impl std::fmt::Display for Error {
 		match self {
 			Error::NoPreviousKey(location) => write!(f, "No previous key in {}", location),
 			Error::MissingKeyValueDelimiter(location) => write!(f, "Missing value for key in {}", location),
+			Error::MissingDirectiveArgument(directive, location) => write!(f, "Missing argument for directive '{}' in {}", directive, location),
+			Error::IncludeNotFound(path, location) => write!(f, "Included file '{}' not found in {}", path.display(), location),
+			Error::IncludeCycle(path, location) => write!(f, "Include cycle detected while including '{}' in {}", path.display(), location),
+			Error::IncludeTooDeep(path, location) => write!(f, "Maximum include depth exceeded while including '{}' in {}", path.display(), location),
 			Error::IoError(error) => write!(f, "I/O error: {}", error),
 		}
 	}
@@ -215,8 +307,8 @@ pub struct TextSourceLocation {
 }
 
 impl TextSourceLocation {
-	fn new(source_name: &str, line_start: usize, line_end: usize) -> Rc<Self> {
-		Rc::new(Self {
+	fn new(source_name: &str, line_start: usize, line_end: usize) -> Arc<Self> {
+		Arc::new(Self {
 			source_name: source_name.to_owned(),
 			line_start,
 			line_end
@@ -234,7 +326,26 @@ impl fmt::Display for TextSourceLocation {
 	}
 }
 
-impl SourceLocation for TextSourceLocation {}
+impl SourceLocation for TextSourceLocation {
+	fn source_name(&self) -> Option<&str> {
+		Some(&self.source_name)
+	}
+
+	fn line(&self) -> Option<usize> {
+		Some(self.line_start)
+	}
+}
+
+/// Files that are currently being parsed.
+///
+/// The depth is counted separately from the files because configuration
+/// sources that are not files are not part of the include stack.
+struct IncludeState {
+	/// Canonical paths of the files to detect include cycles.
+	files: Vec<PathBuf>,
+	/// Number of nested includes of the file that is parsed.
+	depth: usize
+}
 
 struct CurrentValue<'a> {
 	value: String,
@@ -245,7 +356,9 @@ struct CurrentValue<'a> {
 
 /// Implements the text configuration parser.
 pub struct ConfigText {
-	items: HashMap<ConfPath, StringItem>
+	items: HashMap<ConfPath, StringItem>,
+	unset_sections: Vec<ConfPath>,
+	includes: bool
 }
 
 impl ConfigText {
@@ -259,6 +372,114 @@ impl ConfigText {
 		}
 	}
 
+	/// Removes all values of the given key.
+	fn unset_key(&mut self, key: ConfPath) {
+		self.items.insert(key.clone(), StringItem::new(key));
+	}
+
+	/// Removes all values within the given section.
+	fn unset_section(&mut self, section: ConfPath) {
+		self.items.retain(|key, _| !key.starts_with(&section));
+		self.unset_sections.push(section);
+	}
+
+	/// Splits a directive into its name and its argument.
+	///
+	/// Returns `None` if the line does not contain a known directive. Such lines
+	/// are parsed as ordinary key-value pairs.
+	fn split_directive<'l>(&self, line: &'l str) -> Option<(&'l str, &'l str)> {
+		let directive = line.strip_prefix('!')?;
+
+		let (name, argument) = match directive.find(char::is_whitespace) {
+			Some(pos) => (&directive[..pos], directive[pos..].trim()),
+			None => (directive, "")
+		};
+
+		match name {
+			"unset" => Some((name, argument)),
+			"include" | "include-dir" if self.includes => Some((name, argument)),
+			_ => None
+		}
+	}
+
+	fn process_directive(&mut self, (name, argument): (&str, &str), current_section: &ConfPath, path_root: &ConfPath, source_name: &str, line_no: usize, include_state: &mut IncludeState) -> Result<(), Error> {
+		if argument.is_empty() {
+			return Err(Error::MissingDirectiveArgument(name.to_owned(), TextSourceLocation::new(source_name, line_no, line_no)));
+		}
+
+		match name {
+			"unset" => {
+				if argument.starts_with('[') && argument.ends_with(']') {
+					self.unset_section(path_root.push_all(argument[1..argument.len()-1].trim().split('.')));
+				} else {
+					self.unset_key(current_section.push_all(argument.split('.')));
+				}
+
+				Ok(())
+			},
+			"include" => {
+				let include_path = Self::resolve_include_path(source_name, argument);
+
+				self.include(&include_path, path_root, source_name, line_no, include_state)
+			},
+			"include-dir" => {
+				let include_dir = Self::resolve_include_path(source_name, argument);
+
+				let mut include_files = Vec::default();
+				for entry in std::fs::read_dir(&include_dir).map_err(|_| Error::IncludeNotFound(include_dir.clone(), TextSourceLocation::new(source_name, line_no, line_no)))? {
+					let path = entry?.path();
+
+					let is_hidden = path.file_name().is_none_or(|file_name| file_name.to_string_lossy().starts_with('.'));
+					if !is_hidden && path.is_file() && path.extension() == Some(OsStr::new(INCLUDE_DIR_EXTENSION)) {
+						include_files.push(path);
+					}
+				}
+
+				// Include the files in lexical order to make the result deterministic.
+				include_files.sort();
+
+				for include_path in include_files {
+					self.include(&include_path, path_root, source_name, line_no, include_state)?;
+				}
+
+				Ok(())
+			},
+			_ => unreachable!("Unknown directives are not split by split_directive.")
+		}
+	}
+
+	/// Resolves the path of an included file relative to the directory of the
+	/// including configuration source.
+	fn resolve_include_path(source_name: &str, include: &str) -> PathBuf {
+		match Path::new(source_name).parent() {
+			Some(base_dir) => base_dir.join(include),
+			None => PathBuf::from(include)
+		}
+	}
+
+	/// Parses the included file and adds its values to this instance.
+	fn include(&mut self, include_path: &Path, path_root: &ConfPath, source_name: &str, line_no: usize, include_state: &mut IncludeState) -> Result<(), Error> {
+		let canonical_path = include_path.canonicalize().map_err(|_| Error::IncludeNotFound(include_path.to_owned(), TextSourceLocation::new(source_name, line_no, line_no)))?;
+
+		if include_state.files.contains(&canonical_path) {
+			return Err(Error::IncludeCycle(include_path.to_owned(), TextSourceLocation::new(source_name, line_no, line_no)));
+		}
+
+		if include_state.depth >= MAX_INCLUDE_DEPTH {
+			return Err(Error::IncludeTooDeep(include_path.to_owned(), TextSourceLocation::new(source_name, line_no, line_no)));
+		}
+
+		let include_file = File::open(include_path).map_err(|_| Error::IncludeNotFound(include_path.to_owned(), TextSourceLocation::new(source_name, line_no, line_no)))?;
+
+		include_state.files.push(canonical_path);
+		include_state.depth += 1;
+		let result = self.parse(include_file, &include_path.to_string_lossy(), path_root, include_state);
+		include_state.depth -= 1;
+		include_state.files.pop();
+
+		result
+	}
+
 	fn find_start_of_comment(s: &str) -> Option<usize> {
 		let mut chars = s.chars();
 
@@ -276,42 +497,19 @@ impl ConfigText {
 		None
 	}
 
-	/// Parses a text representation into configuration information.
-	/// 
-	/// Any instance of a struct implementing `Read` can be passed to the configuration
-	/// parser. As the second parameter a string identifying the configuration source
-	/// must be passed. This string is used to construct the error location when
-	/// displaying error messages.
-	/// 
-	/// The method returns a new `ConfigText` instance or an error if the file could not
-	/// be parsed.
-	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
-		Self::with_path(conf_source, source_name, &ConfPath::default())
-	}
-
-	/// Parse a text representation of configuration information and fill a `ConfPath`
-	/// with the contained keys. 
-	/// 
-	/// To be able to enumerate the keys of a configuration the
-	/// [`children`](ConfPath::children) method of a
-	/// [`ConfPath`] instance must be used. This variant of the
-	/// [`new`](Self::new) method allows a [`ConfPath`] instance to be passed. This 
-	/// instance is used to construct all configuration paths while parsing the text
-	/// representation. After this method returns the [`ConfPath`] instance can be used
-	/// to explore the contents of the parsed text configuration.
-	pub fn with_path(conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
-		let mut conf = Self {
-			items: HashMap::default()
-		};
-
+	/// Parses a text representation and adds the contained values to this
+	/// instance.
+	///
+	/// The include state tracks the files that are currently being parsed to
+	/// detect include cycles and to limit the include depth.
+	fn parse(&mut self, conf_source: impl Read, source_name: &str, path_root: &ConfPath, include_state: &mut IncludeState) -> Result<(), Error> {
 		let reader = BufReader::new(conf_source);
 
 		let mut current_key: Option<ConfPath> = None;
 		let mut current_value: Option<CurrentValue> = None; // We've to store the TextSourceLocation as well to update it on multi line items.
 		let mut current_section = path_root.clone();
 
-		let mut line_no: usize = 1;
-		for read_line in reader.lines() {
+		for (line_no, read_line) in (1usize..).zip(reader.lines()) {
 			let mut line = read_line?;
 
 			// Detect comments and remove them
@@ -322,16 +520,23 @@ impl ConfigText {
 			let trimed = line.trim();
 			if trimed.is_empty() {
 				// Empty lines reset the current key. A line continuation after an empty line is impossible.
-				conf.put_value(&current_key, &mut current_value);
+				self.put_value(&current_key, &mut current_value);
 				current_key = None;
 			} else if trimed.starts_with('[') && trimed.ends_with(']') {
-				conf.put_value(&current_key, &mut current_value);
+				self.put_value(&current_key, &mut current_value);
 
 				// Update the current section if a section header was found
 				current_section=path_root.push_all(trimed.trim()[1..trimed.len()-1].split('.'));
 
 				// Reset the current key, because we're within an new section
 				current_key = None;
+			} else if let Some(directive) = self.split_directive(trimed) {
+				self.put_value(&current_key, &mut current_value);
+
+				self.process_directive(directive, &current_section, path_root, source_name, line_no, include_state)?;
+
+				// Directives reset the current key like empty lines.
+				current_key = None;
 			} else if trimed.starts_with('|') {
 				// If the first, non white-space character on the line is the line continuation
 				// character the line is appended to the previous line after adding a newline character.
@@ -349,7 +554,7 @@ impl ConfigText {
 				}
 
 			} else {
-				conf.put_value(&current_key, &mut current_value);
+				self.put_value(&current_key, &mut current_value);
 
 				// The line does not start with a white-space or the first character after
 				// the white-space(s) is an equals sign
@@ -379,22 +584,91 @@ impl ConfigText {
 					return Err(Error::MissingKeyValueDelimiter(TextSourceLocation::new(source_name, line_no, line_no)));
 				}
 			}
-
-			line_no+=1;
 		}
 
 		// Final put if there is a value pending
 		if current_value.is_some() {
-			conf.put_value(&current_key, &mut current_value);
+			self.put_value(&current_key, &mut current_value);
 		}
 
+		Ok(())
+	}
+
+	/// Parses a text representation into configuration information.
+	/// 
+	/// Any instance of a struct implementing `Read` can be passed to the configuration
+	/// parser. As the second parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	/// 
+	/// The method returns a new `ConfigText` instance or an error if the file could not
+	/// be parsed.
+	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(conf_source, source_name, &ConfPath::default())
+	}
+
+	/// Parse a text representation of configuration information and fill a `ConfPath`
+	/// with the contained keys. 
+	/// 
+	/// To be able to enumerate the keys of a configuration the
+	/// [`children`](ConfPath::children) method of a
+	/// [`ConfPath`] instance must be used. This variant of the
+	/// [`new`](Self::new) method allows a [`ConfPath`] instance to be passed. This 
+	/// instance is used to construct all configuration paths while parsing the text
+	/// representation. After this method returns the [`ConfPath`] instance can be used
+	/// to explore the contents of the parsed text configuration.
+	pub fn with_path(conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		Self::parse_source(conf_source, source_name, path_root, false)
+	}
+
+	/// Parse a text representation of configuration information and process
+	/// the `!include` and `!include-dir` directives.
+	///
+	/// Works like [`with_path`](Self::with_path) but allows the configuration
+	/// source to include other files. See [Including files](self#including-files)
+	/// for details. Only enable includes for trusted configuration files. The
+	/// directives can read any file the application has access to.
+	///
+	/// This method has the same signature as [`with_path`](Self::with_path). To
+	/// stack configuration files with includes enabled pass it to the generic
+	/// [`stack_config`](super::stack_config) function.
+	pub fn with_includes(conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		Self::parse_source(conf_source, source_name, path_root, true)
+	}
+
+	fn parse_source(conf_source: impl Read, source_name: &str, path_root: &ConfPath, includes: bool) -> Result<Box<Self>, Error> {
+		let mut conf = Self {
+			items: HashMap::default(),
+			unset_sections: Vec::default(),
+			includes
+		};
+
+		// The file itself is part of the include stack to detect if it includes itself.
+		let mut include_state = IncludeState {
+			files: Path::new(source_name).canonicalize().into_iter().collect(),
+			depth: 0
+		};
+
+		conf.parse(conf_source, source_name, path_root, &mut include_state)?;
+
 		Ok(Box::new(conf))
 	}
 }
 
 impl Source for ConfigText {
 	fn get(&self, key: ConfPath) -> Option<StringItem> {
-		self.items.get(&key).cloned()
+		if let Some(item) = self.items.get(&key) {
+			Some(item.clone())
+		} else if self.unset_sections.iter().any(|section| key.starts_with(section)) {
+			// Keys within removed sections are known to have no values.
+			Some(StringItem::new(key))
+		} else {
+			None
+		}
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
 	}
 }
 
@@ -417,6 +691,9 @@ impl Source for ConfigText {
 /// will be used to store all configuration values for enumeration. See
 /// [Enumerating keys](crate#enumerating-keys) for details.
 ///
+/// To stack files of other formats use the generic
+/// [`stack_config`](super::stack_config) function.
+///
 /// ## Example
 ///
 /// ```rust
@@ -436,20 +713,7 @@ impl Source for ConfigText {
 /// stack_config(&mut config, None, &OsString::from("myapp.conf"), &paths[..]).unwrap();
 /// ```
 pub fn stack_config(config: &mut Config, config_path: Option<&mut ConfPath>, file_name: &OsString, paths: &[&Path]) -> Result<(), Error>{
-	let file_name = Path::new(file_name);
-
-	for &path in paths {
-		let this_path = path.join(file_name);
-		if let Ok(config_file) = File::open(&this_path) {
-			if let Some(config_path) = &config_path {
-				config.add_source(ConfigText::with_path(config_file, &this_path.to_string_lossy(), config_path)?);
-			} else {
-				config.add_source(ConfigText::new(config_file, &this_path.to_string_lossy())?);
-			}
-		}
-	}
-
-	Ok(())
+	super::stack_config(config, config_path.as_deref(), file_name, paths, ConfigText::with_path)
 }
 
 #[cfg(test)]
@@ -542,6 +806,177 @@ Key without value
 		let _ = ConfigText::new(config_file.as_bytes(), "myfile").unwrap();
 	}
 
+	#[test]
+	fn unset() {
+		let config_file = r#"
+key1=value1
+!unset key1
+
+key2=value2
+!unset key2
+key2=value2.new
+
+[section]
+!unset key3
+  !unset  sub.key4  # Comment
+
+[removed]
+key5=value5
+key6=value6
+[other]
+!unset [removed]
+[removed]
+key6=value6.new
+"#;
+
+		let conf = ConfigText::new(config_file.as_bytes(), "myfile").unwrap();
+
+		assert_eq!(conf.get(ConfPath::from(["key1"])).unwrap().values().len(), 0);
+		assert_item(conf.get(ConfPath::from(["key2"])).unwrap(), &["value2.new"]);
+		assert_eq!(conf.get(ConfPath::from(["section", "key3"])).unwrap().values().len(), 0);
+		assert_eq!(conf.get(ConfPath::from(["section", "sub", "key4"])).unwrap().values().len(), 0);
+		assert_eq!(conf.get(ConfPath::from(["removed", "key5"])).unwrap().values().len(), 0);
+		assert_eq!(conf.get(ConfPath::from(["removed", "any", "key"])).unwrap().values().len(), 0);
+		assert_item(conf.get(ConfPath::from(["removed", "key6"])).unwrap(), &["value6.new"]);
+		assert!(conf.get(ConfPath::from(["other", "key"])).is_none());
+	}
+
+	#[test]
+	fn unset_stacked() {
+		let mut config = Config::default();
+		config.add_source(ConfigText::new("!unset key1\n!unset [section]\n".as_bytes(), "override").unwrap());
+		config.add_source(ConfigText::new("key1=value1\nkey2=value2\n[section]\nkey3=value3\n".as_bytes(), "defaults").unwrap());
+
+		assert!((config.get(ConfPath::from(["key1"])).try_value() as Result<Option<String>, ConfigError>).unwrap().is_none());
+		assert_eq!((config.get(ConfPath::from(["key2"])).value() as Result<String, ConfigError>).unwrap(), "value2");
+		assert_eq!((config.get(ConfPath::from(["section", "key3"])).values(..) as Result<Vec<String>, ConfigError>).unwrap().len(), 0);
+	}
+
+	#[test]
+	fn unknown_directive() {
+		let config_file = r#"
+!unknown=value1
+!include=value2
+"#;
+
+		// Lines that are not a known directive are ordinary keys.
+		let conf = ConfigText::new(config_file.as_bytes(), "myfile").unwrap();
+
+		assert_item(conf.get(ConfPath::from(["!unknown"])).unwrap(), &["value1"]);
+		assert_item(conf.get(ConfPath::from(["!include"])).unwrap(), &["value2"]);
+	}
+
+	#[test]
+	#[should_panic(expected = "MissingDirectiveArgument(\"unset\", TextSourceLocation { source_name: \"myfile\", line_start: 2, line_end: 2 })")]
+	fn parse_error_missing_argument() {
+		let config_file = r#"
+!unset
+"#;
+
+		let _ = ConfigText::new(config_file.as_bytes(), "myfile").unwrap();
+	}
+
+	#[test]
+	fn include() {
+		let include_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("include");
+		let main_file = include_dir.join("main.conf");
+
+		let conf = ConfigText::with_includes(File::open(&main_file).unwrap(), &main_file.to_string_lossy(), &ConfPath::default()).unwrap();
+
+		assert_item(conf.get(ConfPath::from(["key1"])).unwrap(), &["main", "included"]);
+		assert_item(conf.get(ConfPath::from(["included", "key"])).unwrap(), &["included"]);
+		assert_item(conf.get(ConfPath::from(["section", "key2"])).unwrap(), &["10-first", "20-second"]);
+		assert_item(conf.get(ConfPath::from(["section", "key3"])).unwrap(), &["main"]);
+
+		let key2 = conf.get(ConfPath::from(["section", "key2"])).unwrap();
+		let key2_sources: Vec<String> = key2.values().iter().map(|v| v.source().to_string()).collect();
+		assert_eq!(key2_sources, [
+			format!("conf:{}:2", include_dir.join("main.conf.d").join("10-first.conf").display()),
+			format!("conf:{}:1", include_dir.join("main.conf.d").join("20-second.conf").display())
+		]);
+
+		let included = conf.get(ConfPath::from(["included", "key"])).unwrap();
+		assert_eq!(included.values()[0].source().to_string(), format!("conf:{}:3", include_dir.join("included.conf").display()));
+	}
+
+	#[test]
+	fn include_disabled() {
+		let main_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("include").join("main.conf");
+
+		// Without includes the directives are parsed as keys without a value delimiter.
+		match ConfigText::new(File::open(&main_file).unwrap(), &main_file.to_string_lossy()) {
+			Err(Error::MissingKeyValueDelimiter(location)) => assert_eq!(location.to_string(), format!("conf:{}:2", main_file.display())),
+			_ => panic!("Include processed although includes are disabled.")
+		}
+	}
+
+	#[test]
+	fn include_cycle() {
+		let include_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("include");
+
+		for file_name in [Path::new("self.conf"), &Path::new("cycle").join("a.conf")] {
+			let file = include_dir.join(file_name);
+
+			match ConfigText::with_includes(File::open(&file).unwrap(), &file.to_string_lossy(), &ConfPath::default()) {
+				Err(Error::IncludeCycle(_, _)) => (),
+				_ => panic!("Include cycle not detected for {}.", file.display())
+			}
+		}
+	}
+
+	#[test]
+	fn include_too_deep() {
+		let include_dir = std::env::temp_dir().join(format!("justconfig-include-depth-{}", std::process::id()));
+		std::fs::create_dir_all(&include_dir).unwrap();
+
+		// Every file includes the next one. The last one is included at depth 17.
+		for index in 1..=MAX_INCLUDE_DEPTH + 1 {
+			let content = if index <= MAX_INCLUDE_DEPTH { format!("!include {}.conf\n", index + 1) } else { String::from("key=value\n") };
+			std::fs::write(include_dir.join(format!("{}.conf", index)), content).unwrap();
+		}
+		let root_file = include_dir.join("0.conf");
+		std::fs::write(&root_file, "!include 1.conf\n").unwrap();
+
+		// Files and other configuration sources have the same limit.
+		let in_memory = format!("!include {}", include_dir.join("1.conf").display());
+		let results = [
+			ConfigText::with_includes(in_memory.as_bytes(), "myfile", &ConfPath::default()),
+			ConfigText::with_includes(File::open(&root_file).unwrap(), &root_file.to_string_lossy(), &ConfPath::default())
+		];
+
+		// The maximum depth can be reached.
+		let in_limit = format!("!include {}", include_dir.join("2.conf").display());
+		let in_limit = ConfigText::with_includes(in_limit.as_bytes(), "myfile", &ConfPath::default()).map(|conf| conf.get(ConfPath::from(["key"])).is_some());
+
+		std::fs::remove_dir_all(&include_dir).unwrap();
+
+		for result in results {
+			match result {
+				Err(Error::IncludeTooDeep(path, _)) => assert!(path.ends_with(format!("{}.conf", MAX_INCLUDE_DEPTH + 1))),
+				_ => panic!("Maximum include depth not detected.")
+			}
+		}
+		assert!(in_limit.unwrap());
+	}
+
+	#[test]
+	fn include_missing() {
+		let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("include").join("missing.conf");
+
+		match ConfigText::with_includes(File::open(&file).unwrap(), &file.to_string_lossy(), &ConfPath::default()) {
+			Err(Error::IncludeNotFound(path, location)) => {
+				assert!(path.ends_with("does_not_exist.conf"));
+				assert_eq!(location.to_string(), format!("conf:{}:2", file.display()));
+			},
+			_ => panic!("Missing include not detected.")
+		}
+
+		match ConfigText::with_includes("!include-dir does_not_exist.conf.d".as_bytes(), "myfile", &ConfPath::default()) {
+			Err(Error::IncludeNotFound(path, _)) => assert_eq!(path, Path::new("does_not_exist.conf.d")),
+			_ => panic!("Missing include directory not detected.")
+		}
+	}
+
 	#[test]
 	fn stack() {
 		let paths: [&Path; 3] = [
@@ -580,4 +1015,21 @@ Key without value
 		assert_eq!(key_names[1], "key_p1_p2");
 		assert_eq!(key_names[2], "key_p2");
 	}
+
+	#[test]
+	fn keys() {
+		let config_file = r#"
+key1=value1
+[section]
+key2=value2
+"#;
+
+		let conf = ConfigText::new(config_file.as_bytes(), "myfile").unwrap();
+
+		let keys = conf.keys();
+
+		assert_eq!(keys.len(), 2);
+		assert!(keys.contains(&ConfPath::from(&["key1"])));
+		assert!(keys.contains(&ConfPath::from(&["section", "key2"])));
+	}
 }
@@ -0,0 +1,399 @@
+//! TOML source.
+//!
+//! This source parses a [TOML](https://toml.io) document into configuration
+//! information. It is only available if the `toml` feature is enabled. The
+//! document is parsed by the [`toml`](https://docs.rs/toml) crate.
+//!
+//! Any struct that implements `Read` can be used as a source for configuration
+//! values. The document must be UTF-8 encoded.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::toml::ConfigToml;
+//!
+//! let toml = r#"
+//! title = "example"
+//!
+//! [server]
+//! port = 8_080
+//! hosts = [ "a", "b" ]
+//!
+//! [[server.backend]]
+//! name = "first"
+//!
+//! [[server.backend]]
+//! name = "second"
+//! "#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigToml::new(toml.as_bytes(), "myconfig.toml").unwrap());
+//!
+//! let port: u16 = conf.get(conf.root().push_all(["server", "port"])).value().unwrap();
+//! let hosts: Vec<String> = conf.get(conf.root().push_all(["server", "hosts"])).values(..).unwrap();
+//! let backend: String = conf.get(conf.root().push_all(["server", "backend", "1", "name"])).value().unwrap();
+//!
+//! assert_eq!(port, 8080);
+//! assert_eq!(hosts, ["a", "b"]);
+//! assert_eq!(backend, "second");
+//! ```
+//!
+//! ## Mapping
+//!
+//! The TOML document is mapped onto configuration paths by the following
+//! rules:
+//!
+//! * Tables and dotted keys add a path component for every key.
+//! * Strings, numbers, booleans and dates become the value of the
+//!   configuration item. Underscores within numbers are removed and
+//!   hexadecimal, octal and binary integers are converted to decimal numbers.
+//!   This way numbers can be converted by using their `FromStr` implementation.
+//!   Dates are stored exactly like they were written in the document.
+//! * Arrays become multiple values of the same configuration item. Tables and
+//!   arrays within an array are stored below a sub-path named like their index
+//!   within the array (`0`, `1`, ...). This way `a = [ [ 1, 2 ], [ 3 ] ]`
+//!   results in the items `a.0` and `a.1`. An array that only contains tables
+//!   or arrays does not create an item for the array itself. Empty arrays
+//!   result in a configuration item without values.
+//! * Every table of an array of tables is stored below a sub-path named like
+//!   its index (`0`, `1`, ...). Inline arrays of tables are mapped the same
+//!   way.
+//!
+//! The source locations of the values contain the name of the source, the line
+//! and the column the value starts at.
+//!
+//! ## Stacking configuration files
+//!
+//! TOML files can be stacked by passing [`ConfigToml::with_path`] to
+//! [`stack_config`](super::stack_config).
+use crate::source::Source;
+use crate::item::{StringItem, Value};
+use crate::confpath::ConfPath;
+use super::position::PositionSourceLocation;
+
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+use std::io::Read;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::fmt;
+
+/// Enumeration containing parse errors.
+#[derive(Debug)]
+pub enum Error {
+	/// The document is not valid TOML. The first parameter contains the
+	/// description of the error.
+	Syntax(String, Arc<PositionSourceLocation>),
+	/// An integer does not fit into a 64 bit signed integer. The first
+	/// parameter contains the integer.
+	InvalidInteger(String, Arc<PositionSourceLocation>),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Syntax(message, location) => write!(f, "{} in {}", message, location),
+			Error::InvalidInteger(value, location) => write!(f, "Invalid integer '{}' in {}", value, location),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Converts the byte offsets of the parsed document into source locations.
+struct Locator<'a> {
+	document: &'a str,
+	source_name: &'a str
+}
+
+impl <'a> Locator<'a> {
+	/// Returns the line and the column of the character starting at `offset`.
+	fn location(&self, offset: usize) -> Arc<PositionSourceLocation> {
+		let before = &self.document[..offset.min(self.document.len())];
+		let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+
+		PositionSourceLocation::new("toml", self.source_name, before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+	}
+}
+
+/// Implements the TOML source.
+pub struct ConfigToml {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigToml {
+	/// Parses a TOML document into configuration information.
+	///
+	/// Any instance of a struct implementing `Read` can be passed to the parser.
+	/// As the second parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	///
+	/// The method returns a new `ConfigToml` instance or an error if the
+	/// document could not be parsed.
+	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(conf_source, source_name, &ConfPath::default())
+	}
+
+	/// Parses a TOML document and fills a `ConfPath` with the contained keys.
+	///
+	/// This method works like [`ConfigText::with_path`](super::text::ConfigText::with_path).
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the document.
+	pub fn with_path(mut conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		let mut document = String::new();
+		conf_source.read_to_string(&mut document)?;
+
+		let locator = Locator {
+			document: &document,
+			source_name
+		};
+
+		let table = DeTable::parse(&document).map_err(|error| Error::Syntax(error.message().to_owned(), locator.location(error.span().map_or(0, |span| span.start))))?;
+
+		let mut conf = Self {
+			items: HashMap::default()
+		};
+		conf.flatten_table(&locator, path_root, table.into_inner())?;
+
+		Ok(Box::new(conf))
+	}
+
+	fn item(&mut self, key: &ConfPath) -> &mut StringItem {
+		self.items.entry(key.clone()).or_insert_with(|| StringItem::new(key.clone()))
+	}
+
+	fn flatten_table(&mut self, locator: &Locator, path: &ConfPath, table: DeTable) -> Result<(), Error> {
+		for (key, value) in table {
+			self.flatten(locator, &path.push(key.get_ref()), value)?;
+		}
+
+		Ok(())
+	}
+
+	fn flatten(&mut self, locator: &Locator, path: &ConfPath, value: Spanned<DeValue>) -> Result<(), Error> {
+		let span = value.span();
+		let location = locator.location(span.start);
+
+		let value = match value.into_inner() {
+			DeValue::String(value) => value.into_owned(),
+			DeValue::Integer(integer) => {
+				// Convert hexadecimal, octal and binary integers to decimal numbers.
+				i64::from_str_radix(integer.as_str(), integer.radix()).map_err(|_| Error::InvalidInteger(locator.document[span].to_owned(), location.clone()))?.to_string()
+			},
+			DeValue::Float(float) => float.as_str().to_owned(),
+			DeValue::Boolean(value) => value.to_string(),
+			DeValue::Datetime(_) => locator.document[span].to_owned(),
+			DeValue::Array(values) => {
+				// An empty array results in an item without values.
+				if values.is_empty() {
+					self.item(path);
+				}
+
+				for (index, value) in values.into_iter().enumerate() {
+					match value.get_ref() {
+						DeValue::Table(_) | DeValue::Array(_) => self.flatten(locator, &path.push(&index.to_string()), value)?,
+						_ => self.flatten(locator, path, value)?
+					}
+				}
+
+				return Ok(());
+			},
+			DeValue::Table(table) => return self.flatten_table(locator, path, table)
+		};
+
+		self.item(path).push(Value::new(value, location));
+
+		Ok(())
+	}
+}
+
+impl Source for ConfigToml {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn prepare_test_config(toml: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigToml::new(toml.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	fn values(c: &Config, key: &[&str]) -> Vec<String> {
+		c.get(ConfPath::from(key)).values(..).unwrap()
+	}
+
+	#[test]
+	fn parsing() {
+		let c = prepare_test_config(r#"
+# Comment
+basic = "a \"quoted\"\tvalue \u00e4" # Trailing comment
+literal = 'C:\path'
+"quoted key" = 1
+dotted.key = true
+empty = ""
+multi_basic = """
+line 1 \
+   line 2
+line 3"""
+multi_literal = '''
+raw \n ''value'''''
+numbers = [ 1_000, 0xff, 0o17, 0b101, +5, -3.5e2, inf ]
+date = 1979-05-27 07:32:00Z
+dates = [ 2000-02-29, 1979-05-27T00:32:00.999999-07:00, 07:32:00.5 ]
+inline = { a = 1, b.c = "x" }
+tables = [ { name = "first" }, { name = "second" } ]
+empty_list = []
+
+[section.sub]
+key = "value"
+"#);
+
+		assert_eq!(values(&c, &["basic"]), ["a \"quoted\"\tvalue \u{e4}"]);
+		assert_eq!(values(&c, &["literal"]), ["C:\\path"]);
+		assert_eq!(values(&c, &["quoted key"]), ["1"]);
+		assert_eq!(values(&c, &["dotted", "key"]), ["true"]);
+		assert_eq!(values(&c, &["empty"]), [""]);
+		assert_eq!(values(&c, &["multi_basic"]), ["line 1 line 2\nline 3"]);
+		assert_eq!(values(&c, &["multi_literal"]), ["raw \\n ''value''"]);
+		assert_eq!(values(&c, &["numbers"]), ["1000", "255", "15", "5", "5", "-3.5e2", "inf"]);
+		assert_eq!(values(&c, &["date"]), ["1979-05-27 07:32:00Z"]);
+		assert_eq!(values(&c, &["dates"]), ["2000-02-29", "1979-05-27T00:32:00.999999-07:00", "07:32:00.5"]);
+		assert_eq!(values(&c, &["inline", "a"]), ["1"]);
+		assert_eq!(values(&c, &["inline", "b", "c"]), ["x"]);
+		assert_eq!(values(&c, &["tables", "0", "name"]), ["first"]);
+		assert_eq!(values(&c, &["tables", "1", "name"]), ["second"]);
+		assert!(values(&c, &["empty_list"]).is_empty());
+		assert_eq!(values(&c, &["section", "sub", "key"]), ["value"]);
+	}
+
+	#[test]
+	fn array_of_tables() {
+		let c = prepare_test_config(r#"
+[[fruit]]
+name = "apple"
+
+[fruit.physical]
+color = "red"
+
+[[fruit]]
+name = "banana"
+
+[[fruit.variety]]
+name = "plantain"
+"#);
+
+		assert_eq!(values(&c, &["fruit", "0", "name"]), ["apple"]);
+		assert_eq!(values(&c, &["fruit", "0", "physical", "color"]), ["red"]);
+		assert_eq!(values(&c, &["fruit", "1", "name"]), ["banana"]);
+		assert_eq!(values(&c, &["fruit", "1", "variety", "0", "name"]), ["plantain"]);
+	}
+
+	#[test]
+	fn inline_array_of_tables() {
+		let tables = prepare_test_config("[[s]]\nh = 1\n[[s]]\nh = 2\n");
+		let inline = prepare_test_config("s = [ { h = 1 }, { h = 2 } ]\n");
+
+		assert_eq!(tables.keys(), inline.keys());
+		assert_eq!(values(&inline, &["s", "0", "h"]), ["1"]);
+		assert_eq!(values(&inline, &["s", "1", "h"]), ["2"]);
+		assert!(inline.unused_keys().is_empty());
+	}
+
+	#[test]
+	fn source_location() {
+		let c = prepare_test_config("a = \"x\"\nb = [\n  1,\n  2 ]\n");
+
+		let error = (c.get(ConfPath::from(&["b"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "More than 1 value found for key b@['toml:myfile:3:3', 'toml:myfile:4:3']");
+
+		let error = (c.get(ConfPath::from(&["a"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'toml:myfile:1:5'");
+	}
+
+	#[test]
+	fn nested_arrays() {
+		let c = prepare_test_config("a = [ [ 1, 2 ], [ 3 ], 4, { b = 5 } ]\nempty = [ [] ]\n");
+
+		assert_eq!(values(&c, &["a"]), ["4"]);
+		assert_eq!(values(&c, &["a", "0"]), ["1", "2"]);
+		assert_eq!(values(&c, &["a", "1"]), ["3"]);
+		assert_eq!(values(&c, &["a", "3", "b"]), ["5"]);
+		assert!(values(&c, &["empty", "0"]).is_empty());
+		assert!(c.get(ConfPath::from(&["empty"])).is_err());
+	}
+
+	#[test]
+	fn syntax_errors() {
+		fn parse_error(toml: &str) -> String {
+			ConfigToml::new(toml.as_bytes(), "myfile").err().unwrap().to_string()
+		}
+
+		assert_eq!(parse_error("a = 1\na = 2"), "duplicate key in toml:myfile:2:1");
+		assert_eq!(parse_error("a = \"open"), "invalid basic string, expected `\"` in toml:myfile:1:10");
+		assert_eq!(parse_error("a = 01"), "unexpected leading zero, expected nothing in toml:myfile:1:5");
+		assert_eq!(parse_error("a = 9223372036854775808"), "Invalid integer '9223372036854775808' in toml:myfile:1:5");
+
+		for toml in ["a = 1 b = 2", "a = \"\\x\"", "a = yes", "[table", "a = 1.", "a = 2-3", "a = 0x_1", "a = 1979-02-30"] {
+			assert!(matches!(ConfigToml::new(toml.as_bytes(), "myfile"), Err(Error::Syntax(_, _))), "Invalid document not detected: {}", toml);
+		}
+	}
+
+	#[test]
+	fn table_redefinition() {
+		for toml in ["[a]\nx = 1\n\n[a]\ny = 2", "[[a]]\n[a]", "[a]\n[[a]]", "a = { x = 1 }\n[a]", "[a.b]\nx = 1\n[a]\nb = 2", "a.b = 1\na = 2", "a = 1\na.b = 2", "a = 1\n[a]"] {
+			assert!(matches!(ConfigToml::new(toml.as_bytes(), "myfile"), Err(Error::Syntax(_, _))), "Redefinition not detected: {}", toml);
+		}
+
+		// Tables created implicitly by a sub-table can be defined once.
+		let c = prepare_test_config("[a.b]\nx = 1\n[a]\ny = 2\n");
+		assert_eq!(values(&c, &["a", "b", "x"]), ["1"]);
+		assert_eq!(values(&c, &["a", "y"]), ["2"]);
+	}
+
+	#[test]
+	fn stacking() {
+		let mut c = Config::default();
+		let root = c.root();
+		let paths: [&std::path::Path; 2] = [
+			&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("toml").join("first"),
+			&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("toml").join("second")
+		];
+
+		crate::sources::stack_config(&mut c, Some(&root), std::ffi::OsStr::new("config.toml"), &paths, ConfigToml::with_path).unwrap();
+
+		assert_eq!(values(&c, &["server", "port"]), ["8080"]);
+		assert_eq!(values(&c, &["server", "host"]), ["second"]);
+		assert_eq!(root.children().count(), 1);
+	}
+}
@@ -0,0 +1,607 @@
+//! YAML source.
+//!
+//! This source parses a [YAML](https://yaml.org) document into configuration
+//! information. It is only available if the `yaml` feature is enabled.
+//!
+//! Any struct that implements `Read` can be used as a source for configuration
+//! values. The document must be UTF-8 encoded.
+//!
+//! ```rust
+//! use justconfig::Config;
+//! use justconfig::item::ValueExtractor;
+//! use justconfig::sources::yaml::ConfigYaml;
+//!
+//! let yaml = r#"
+//! defaults: &defaults
+//!   timeout: 30
+//! server:
+//!   port: 8080
+//!   hosts: [ a, b ]
+//!   limits:
+//!     <<: *defaults
+//!     connections: 10
+//! "#;
+//!
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigYaml::new(yaml.as_bytes(), "myconfig.yaml").unwrap());
+//!
+//! let port: u16 = conf.get(conf.root().push_all(["server", "port"])).value().unwrap();
+//! let hosts: Vec<String> = conf.get(conf.root().push_all(["server", "hosts"])).values(..).unwrap();
+//! let timeout: u32 = conf.get(conf.root().push_all(["server", "limits", "timeout"])).value().unwrap();
+//!
+//! assert_eq!(port, 8080);
+//! assert_eq!(hosts, ["a", "b"]);
+//! assert_eq!(timeout, 30);
+//! ```
+//!
+//! ## Mapping
+//!
+//! The YAML document is mapped onto configuration paths by the following
+//! rules:
+//!
+//! * The document must contain a mapping. Its keys are the first component of
+//!   the configuration paths.
+//! * Nested mappings add a path component for every key. Keys must be scalars
+//!   and must be unique within their mapping.
+//! * Scalars become the value of the configuration item. They are stored
+//!   exactly like they were written in the document, after processing the
+//!   quoting and block styles of YAML.
+//! * Sequences become multiple values of the same configuration item. Mappings
+//!   within a sequence are stored below a sub-path named like their index
+//!   within the sequence (`0`, `1`, ...). A sequence that only contains
+//!   mappings does not create an item for the sequence itself. Empty sequences
+//!   result in a configuration item without values. Sequences must not contain
+//!   other sequences.
+//! * `null` and `~` result in a configuration item without values. This hides
+//!   the values of configuration sources with a lower priority.
+//! * Aliases are replaced by the node carrying the anchor. The merge key `<<`
+//!   adds all keys of the referenced mappings that are not already contained
+//!   within the mapping. To protect against documents that expand to huge
+//!   trees by nesting aliases, at most 100000 nodes can be created by
+//!   resolving aliases.
+//!
+//! The source locations of the values contain the name of the source, the line
+//! and the column the value starts at.
+//!
+//! ## Multiple documents
+//!
+//! A YAML stream can contain multiple documents separated by `---`.
+//! [`ConfigYaml::new`] and [`ConfigYaml::with_path`] reject such streams.
+//! Use [`ConfigYaml::documents`] to get a separate configuration source for
+//! every document and select the ones to add to the configuration.
+use crate::source::Source;
+use crate::item::{StringItem, Value};
+use crate::confpath::ConfPath;
+use super::position::PositionSourceLocation;
+
+use yaml_rust2::parser::Parser;
+use yaml_rust2::scanner::{Marker, ScanError, TScalarStyle};
+use yaml_rust2::Event;
+
+use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use std::str::Chars;
+use std::sync::Arc;
+use std::fmt;
+
+/// Maximum nesting depth of mappings and sequences.
+const MAX_NESTING_DEPTH: usize = 128;
+
+/// Maximum number of nodes created by resolving aliases.
+const MAX_ALIAS_NODES: usize = 100_000;
+
+/// Enumeration containing parse errors.
+#[derive(Debug)]
+pub enum Error {
+	/// The document is not valid YAML. The first parameter contains the
+	/// description of the error.
+	Syntax(String, Arc<PositionSourceLocation>),
+	/// The key of a mapping is not a scalar.
+	InvalidKey(Arc<PositionSourceLocation>),
+	/// A key was defined more than once within a mapping. The first parameter
+	/// contains the key.
+	DuplicateKey(String, Arc<PositionSourceLocation>),
+	/// The merge key `<<` does not reference a mapping or a sequence of
+	/// mappings.
+	InvalidMerge(Arc<PositionSourceLocation>),
+	/// A sequence contains another sequence.
+	NestedSequence(Arc<PositionSourceLocation>),
+	/// The document does not contain a mapping at the top level.
+	NotAMapping(Arc<PositionSourceLocation>),
+	/// The stream contains more than one document. The location points to the
+	/// start of the second document.
+	MultipleDocuments(Arc<PositionSourceLocation>),
+	/// Mappings and sequences are nested too deeply.
+	TooDeep(Arc<PositionSourceLocation>),
+	/// Resolving the aliases of the document creates too many nodes. The
+	/// location points to the alias that exceeded the limit.
+	TooManyAliasNodes(Arc<PositionSourceLocation>),
+	/// An I/O error occurred while reading.
+	IoError(std::io::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Syntax(message, location) => write!(f, "{} in {}", message, location),
+			Error::InvalidKey(location) => write!(f, "Mapping keys must be scalars in {}", location),
+			Error::DuplicateKey(key, location) => write!(f, "Duplicate key '{}' in {}", key, location),
+			Error::InvalidMerge(location) => write!(f, "Only mappings can be merged in {}", location),
+			Error::NestedSequence(location) => write!(f, "Nested sequences are not supported in {}", location),
+			Error::NotAMapping(location) => write!(f, "The document must contain a mapping in {}", location),
+			Error::MultipleDocuments(location) => write!(f, "Unexpected additional document in {}", location),
+			Error::TooDeep(location) => write!(f, "Maximum nesting depth exceeded in {}", location),
+			Error::TooManyAliasNodes(location) => write!(f, "Maximum number of nodes created by aliases exceeded in {}", location),
+			Error::IoError(error) => write!(f, "I/O error: {}", error),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::IoError(source) => Some(source),
+			_ => None
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(io_error: std::io::Error) -> Self {
+		Error::IoError(io_error)
+	}
+}
+
+/// Node of a YAML document with all aliases resolved.
+#[derive(Clone)]
+enum Node {
+	Null,
+	Scalar(String, Arc<PositionSourceLocation>),
+	Sequence(Vec<Node>, Arc<PositionSourceLocation>),
+	Mapping(Vec<(String, Arc<PositionSourceLocation>, Node)>)
+}
+
+impl Node {
+	/// Returns the number of nodes within this tree including the node itself.
+	fn size(&self) -> usize {
+		match self {
+			Node::Null | Node::Scalar(..) => 1,
+			Node::Sequence(nodes, _) => 1 + nodes.iter().map(Node::size).sum::<usize>(),
+			Node::Mapping(entries) => 1 + entries.iter().map(|(_, _, node)| node.size()).sum::<usize>()
+		}
+	}
+}
+
+/// Builds the nodes of the documents from the events of the YAML parser.
+struct Loader<'a> {
+	parser: Parser<Chars<'a>>,
+	source_name: &'a str,
+	anchors: HashMap<usize, Node>,
+	/// Number of nodes created by resolving aliases so far.
+	alias_nodes: usize
+}
+
+impl <'a> Loader<'a> {
+	fn location(&self, marker: &Marker) -> Arc<PositionSourceLocation> {
+		// The column of the marker starts at zero.
+		PositionSourceLocation::new("yaml", self.source_name, marker.line(), marker.col() + 1)
+	}
+
+	fn next(&mut self) -> Result<(Event, Arc<PositionSourceLocation>), Error> {
+		match self.parser.next_token() {
+			Ok((event, marker)) => Ok((event, self.location(&marker))),
+			Err(error) => Err(self.syntax_error(error))
+		}
+	}
+
+	fn syntax_error(&self, error: ScanError) -> Error {
+		Error::Syntax(error.info().to_owned(), self.location(error.marker()))
+	}
+
+	/// Returns the next document of the stream together with the location it
+	/// starts at. Returns `None` at the end of the stream.
+	fn document(&mut self) -> Result<Option<(Node, Arc<PositionSourceLocation>)>, Error> {
+		loop {
+			match self.next()? {
+				(Event::StreamStart, _) | (Event::DocumentEnd, _) => (),
+				(Event::StreamEnd, _) => return Ok(None),
+				(Event::DocumentStart, location) => {
+					let (event, node_location) = self.next()?;
+					return Ok(Some((self.node(event, node_location, 0)?, location)));
+				},
+				(_, location) => return Err(Error::Syntax(String::from("unexpected event"), location))
+			}
+		}
+	}
+
+	fn node(&mut self, event: Event, location: Arc<PositionSourceLocation>, depth: usize) -> Result<Node, Error> {
+		if depth >= MAX_NESTING_DEPTH {
+			return Err(Error::TooDeep(location));
+		}
+
+		let (node, anchor) = match event {
+			Event::Scalar(value, style, anchor, _) => {
+				let is_null = style == TScalarStyle::Plain && matches!(value.as_str(), "" | "~" | "null" | "Null" | "NULL");
+				(if is_null { Node::Null } else { Node::Scalar(value, location) }, anchor)
+			},
+			Event::SequenceStart(anchor, _) => {
+				let mut nodes = Vec::new();
+				loop {
+					match self.next()? {
+						(Event::SequenceEnd, _) => break,
+						(event, location) => nodes.push(self.node(event, location, depth + 1)?)
+					}
+				}
+				(Node::Sequence(nodes, location), anchor)
+			},
+			Event::MappingStart(anchor, _) => {
+				let mut entries = Vec::new();
+				loop {
+					let (key, key_location) = match self.next()? {
+						(Event::MappingEnd, _) => break,
+						(Event::Scalar(key, ..), location) => (key, location),
+						(event, location) => {
+							// Aliases are allowed as keys if they reference a scalar.
+							match self.node(event, location.clone(), depth + 1)? {
+								Node::Scalar(key, _) => (key, location),
+								_ => return Err(Error::InvalidKey(location))
+							}
+						}
+					};
+
+					let (event, location) = self.next()?;
+					entries.push((key, key_location, self.node(event, location, depth + 1)?));
+				}
+				(Node::Mapping(entries), anchor)
+			},
+			Event::Alias(anchor) => {
+				// The parser makes sure that only known anchors are referenced.
+				let node = match self.anchors.get(&anchor) {
+					Some(node) => node,
+					None => return Ok(Node::Null)
+				};
+
+				// Count the nodes before copying them to stop exponential expansion early.
+				self.alias_nodes += node.size();
+				if self.alias_nodes > MAX_ALIAS_NODES {
+					return Err(Error::TooManyAliasNodes(location));
+				}
+
+				return Ok(node.clone());
+			},
+			_ => return Err(Error::Syntax(String::from("unexpected event"), location))
+		};
+
+		// Anchor IDs start at 1. Zero means that the node has no anchor.
+		if anchor > 0 {
+			self.anchors.insert(anchor, node.clone());
+		}
+
+		Ok(node)
+	}
+}
+
+/// Implements the YAML source.
+pub struct ConfigYaml {
+	items: HashMap<ConfPath, StringItem>
+}
+
+impl ConfigYaml {
+	/// Parses a YAML document into configuration information.
+	///
+	/// Any instance of a struct implementing `Read` can be passed to the parser.
+	/// As the second parameter a string identifying the configuration source
+	/// must be passed. This string is used to construct the error location when
+	/// displaying error messages.
+	///
+	/// The method returns a new `ConfigYaml` instance or an error if the
+	/// document could not be parsed. If the stream contains more than one
+	/// document [`Error::MultipleDocuments`] is returned.
+	pub fn new(conf_source: impl Read, source_name: &str) -> Result<Box<Self>, Error> {
+		Self::with_path(conf_source, source_name, &ConfPath::default())
+	}
+
+	/// Parses a YAML document and fills a `ConfPath` with the contained keys.
+	///
+	/// This method works like [`ConfigText::with_path`](super::text::ConfigText::with_path).
+	/// The passed [`ConfPath`] instance is used to construct all
+	/// configuration paths while parsing the document.
+	pub fn with_path(conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Box<Self>, Error> {
+		let mut documents = Self::load(conf_source, source_name, path_root, false)?;
+
+		Ok(Box::new(documents.pop().unwrap_or_else(|| Self { items: HashMap::default() })))
+	}
+
+	/// Parses a stream of YAML documents and returns a configuration source
+	/// for every document.
+	///
+	/// The sources are returned in the order the documents appear within the
+	/// stream. All of them share the passed [`ConfPath`] instance.
+	///
+	/// ```rust
+	/// use justconfig::Config;
+	/// use justconfig::item::ValueExtractor;
+	/// use justconfig::sources::yaml::ConfigYaml;
+	///
+	/// let yaml = "port: 80\n---\nport: 8080\n";
+	///
+	/// let mut conf = Config::default();
+	/// let mut documents = ConfigYaml::documents(yaml.as_bytes(), "myconfig.yaml", &conf.root()).unwrap();
+	/// conf.add_source(documents.remove(1));
+	///
+	/// let port: u16 = conf.get(conf.root().push("port")).value().unwrap();
+	/// assert_eq!(port, 8080);
+	/// ```
+	pub fn documents(conf_source: impl Read, source_name: &str, path_root: &ConfPath) -> Result<Vec<Box<Self>>, Error> {
+		Ok(Self::load(conf_source, source_name, path_root, true)?.into_iter().map(Box::new).collect())
+	}
+
+	fn load(mut conf_source: impl Read, source_name: &str, path_root: &ConfPath, multiple: bool) -> Result<Vec<Self>, Error> {
+		let mut stream = String::new();
+		conf_source.read_to_string(&mut stream)?;
+
+		let mut loader = Loader {
+			parser: Parser::new_from_str(&stream),
+			source_name,
+			anchors: HashMap::default(),
+			alias_nodes: 0
+		};
+
+		let mut documents = Vec::new();
+		while let Some((node, location)) = loader.document()? {
+			if !multiple && !documents.is_empty() {
+				return Err(Error::MultipleDocuments(location));
+			}
+
+			let mut conf = Self {
+				items: HashMap::default()
+			};
+
+			match node {
+				Node::Mapping(_) => conf.flatten(path_root, node)?,
+				Node::Null => (),
+				_ => return Err(Error::NotAMapping(location))
+			}
+
+			documents.push(conf);
+		}
+
+		Ok(documents)
+	}
+
+	fn item(&mut self, key: &ConfPath) -> &mut StringItem {
+		self.items.entry(key.clone()).or_insert_with(|| StringItem::new(key.clone()))
+	}
+
+	fn flatten(&mut self, path: &ConfPath, node: Node) -> Result<(), Error> {
+		match node {
+			Node::Null => { self.item(path); },
+			Node::Scalar(value, location) => self.item(path).push(Value::new(value, location)),
+			Node::Sequence(nodes, _) => {
+				// An empty sequence results in an item without values.
+				if nodes.is_empty() {
+					self.item(path);
+				}
+
+				for (index, node) in nodes.into_iter().enumerate() {
+					match node {
+						Node::Mapping(_) => self.flatten(&path.push(&index.to_string()), node)?,
+						Node::Sequence(_, location) => return Err(Error::NestedSequence(location)),
+						_ => self.flatten(path, node)?
+					}
+				}
+			},
+			Node::Mapping(entries) => {
+				for (key, value) in Self::merge(entries)? {
+					self.flatten(&path.push(&key), value)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Resolves the merge keys of a mapping and checks for duplicate keys.
+	fn merge(entries: Vec<(String, Arc<PositionSourceLocation>, Node)>) -> Result<Vec<(String, Node)>, Error> {
+		let mut keys = HashSet::new();
+		let mut merged = Vec::new();
+		let mut result = Vec::new();
+
+		for (key, location, value) in entries {
+			if key == "<<" {
+				match value {
+					Node::Mapping(_) => merged.push((location, value)),
+					Node::Sequence(nodes, _) => merged.extend(nodes.into_iter().map(|node| (location.clone(), node))),
+					_ => return Err(Error::InvalidMerge(location))
+				}
+			} else if keys.insert(key.clone()) {
+				result.push((key, value));
+			} else {
+				return Err(Error::DuplicateKey(key, location));
+			}
+		}
+
+		// Keys of earlier merged mappings override keys of later ones.
+		for (location, node) in merged {
+			match node {
+				Node::Mapping(entries) => {
+					for (key, value) in Self::merge(entries)? {
+						if keys.insert(key.clone()) {
+							result.push((key, value));
+						}
+					}
+				},
+				_ => return Err(Error::InvalidMerge(location))
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+impl Source for ConfigYaml {
+	fn get(&self, key: ConfPath) -> Option<StringItem> {
+		self.items.get(&key).cloned()
+	}
+
+	fn keys(&self) -> Vec<ConfPath> {
+		self.items.keys().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+
+	fn prepare_test_config(yaml: &str) -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigYaml::new(yaml.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	fn values(c: &Config, key: &[&str]) -> Vec<String> {
+		c.get(ConfPath::from(key)).values(..).unwrap()
+	}
+
+	#[test]
+	fn parsing() {
+		let c = prepare_test_config(r#"
+plain: value
+quoted: "a\tb"
+single: 'it''s'
+number: 0x10
+empty:
+tilde: ~
+quoted_null: "null"
+literal: |
+  line 1
+  line 2
+list:
+  - a
+  - b
+flow: [ 1, 2 ]
+tables:
+  - name: first
+  - name: second
+nested:
+  key: value
+"#);
+
+		assert_eq!(values(&c, &["plain"]), ["value"]);
+		assert_eq!(values(&c, &["quoted"]), ["a\tb"]);
+		assert_eq!(values(&c, &["single"]), ["it's"]);
+		assert_eq!(values(&c, &["number"]), ["0x10"]);
+		assert!(values(&c, &["empty"]).is_empty());
+		assert!(values(&c, &["tilde"]).is_empty());
+		assert_eq!(values(&c, &["quoted_null"]), ["null"]);
+		assert_eq!(values(&c, &["literal"]), ["line 1\nline 2\n"]);
+		assert_eq!(values(&c, &["list"]), ["a", "b"]);
+		assert_eq!(values(&c, &["flow"]), ["1", "2"]);
+		assert_eq!(values(&c, &["tables", "0", "name"]), ["first"]);
+		assert_eq!(values(&c, &["tables", "1", "name"]), ["second"]);
+		assert_eq!(values(&c, &["nested", "key"]), ["value"]);
+	}
+
+	#[test]
+	fn anchors() {
+		let c = prepare_test_config(r#"
+base: &base
+  host: localhost
+  port: 80
+extra: &extra
+  port: 8080
+  debug: true
+list: &list [ a, b ]
+server:
+  <<: [ *base, *extra ]
+  port: 443
+  hosts: *list
+"#);
+
+		assert_eq!(values(&c, &["server", "host"]), ["localhost"]);
+		assert_eq!(values(&c, &["server", "port"]), ["443"]);
+		assert_eq!(values(&c, &["server", "debug"]), ["true"]);
+		assert_eq!(values(&c, &["server", "hosts"]), ["a", "b"]);
+	}
+
+	#[test]
+	fn sequence_of_mappings() {
+		let mut c = Config::default();
+		c.add_source(ConfigYaml::new("tables: [ { a: 1 }, { a: 2 } ]\n".as_bytes(), "myfile").unwrap());
+		c.add_source(crate::sources::text::ConfigText::new("tables=fallback\n".as_bytes(), "fallback").unwrap());
+
+		assert_eq!(values(&c, &["tables", "0", "a"]), ["1"]);
+		assert_eq!(values(&c, &["tables", "1", "a"]), ["2"]);
+		assert!(!ConfigYaml::new("tables: [ { a: 1 } ]\n".as_bytes(), "myfile").unwrap().keys().contains(&ConfPath::from(&["tables"])));
+
+		// The sequence does not hide the values of sources with a lower priority.
+		assert_eq!(values(&c, &["tables"]), ["fallback"]);
+	}
+
+	#[test]
+	fn documents() {
+		let yaml = "a: 1\n---\na: 2\n";
+
+		match ConfigYaml::new(yaml.as_bytes(), "myfile") {
+			Err(Error::MultipleDocuments(location)) => assert_eq!(location.to_string(), "yaml:myfile:2:1"),
+			_ => panic!("Multiple documents not detected.")
+		}
+
+		let documents = ConfigYaml::documents(yaml.as_bytes(), "myfile", &ConfPath::default()).unwrap();
+		assert_eq!(documents.len(), 2);
+
+		let mut c = Config::default();
+		for document in documents.into_iter().rev() {
+			c.add_source(document);
+		}
+		assert_eq!(values(&c, &["a"]), ["2"]);
+	}
+
+	#[test]
+	fn source_location() {
+		let c = prepare_test_config("a: x\nb:\n  - 1\n  - 2\n");
+
+		let error = (c.get(ConfPath::from(&["b"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "More than 1 value found for key b@['yaml:myfile:3:5', 'yaml:myfile:4:5']");
+
+		let error = (c.get(ConfPath::from(&["a"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'yaml:myfile:1:4'");
+	}
+
+	#[test]
+	fn errors() {
+		fn parse_error(yaml: &str) -> String {
+			ConfigYaml::new(yaml.as_bytes(), "myfile").err().unwrap().to_string()
+		}
+
+		assert_eq!(parse_error("a: 1\na: 2\n"), "Duplicate key 'a' in yaml:myfile:2:1");
+		assert_eq!(parse_error("- a\n- b\n"), "The document must contain a mapping in yaml:myfile:1:1");
+		assert_eq!(parse_error("a: [ [ 1 ] ]\n"), "Nested sequences are not supported in yaml:myfile:1:6");
+		assert_eq!(parse_error("a: { b: 1\n"), "while parsing a flow mapping, did not find expected ',' or '}' in yaml:myfile:2:1");
+		assert_eq!(parse_error("a: 1\n<<: 2\n"), "Only mappings can be merged in yaml:myfile:2:1");
+	}
+
+	#[test]
+	fn alias_expansion() {
+		let yaml = r#"
+a: &a [x, x, x, x, x, x, x, x, x, x]
+b: &b { 0: *a, 1: *a, 2: *a, 3: *a, 4: *a, 5: *a, 6: *a, 7: *a, 8: *a, 9: *a }
+c: &c { 0: *b, 1: *b, 2: *b, 3: *b, 4: *b, 5: *b, 6: *b, 7: *b, 8: *b, 9: *b }
+d: &d { 0: *c, 1: *c, 2: *c, 3: *c, 4: *c, 5: *c, 6: *c, 7: *c, 8: *c, 9: *c }
+e: &e { 0: *d, 1: *d, 2: *d, 3: *d, 4: *d, 5: *d, 6: *d, 7: *d, 8: *d, 9: *d }
+f: &f { 0: *e, 1: *e, 2: *e, 3: *e, 4: *e, 5: *e, 6: *e, 7: *e, 8: *e, 9: *e }
+g: &g { 0: *f, 1: *f, 2: *f, 3: *f, 4: *f, 5: *f, 6: *f, 7: *f, 8: *f, 9: *f }
+h: &h { 0: *g, 1: *g, 2: *g, 3: *g, 4: *g, 5: *g, 6: *g, 7: *g, 8: *g, 9: *g }
+i: &i { 0: *h, 1: *h, 2: *h, 3: *h, 4: *h, 5: *h, 6: *h, 7: *h, 8: *h, 9: *h }
+"#;
+
+		match ConfigYaml::new(yaml.as_bytes(), "myfile") {
+			Err(Error::TooManyAliasNodes(location)) => assert_eq!(location.to_string(), "yaml:myfile:6:61"),
+			_ => panic!("Alias expansion not limited.")
+		}
+	}
+}
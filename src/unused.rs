@@ -0,0 +1,217 @@
+//! Detection of configuration keys that were never read.
+//!
+//! Typos in configuration files are easy to miss. A misspelled key like
+//! `max_conection=10` is stored by the configuration source but never read by
+//! the application. Therefore the configuration silently falls back to the
+//! default value.
+//!
+//! [`Config`](crate::Config) keeps track of all keys that were requested by
+//! calling [`get`](crate::Config::get). After the application read its
+//! configuration [`unused_keys`](crate::Config::unused_keys) returns a list of
+//! all keys known to the configuration sources that were never requested.
+//! To treat unused keys as an error call
+//! [`check_unused`](crate::Config::check_unused). It returns a
+//! [`ConfigError::UnusedKeys`](crate::error::ConfigError::UnusedKeys) error
+//! listing all unused keys.
+//!
+//! For every unused key the most similar key that was requested by the
+//! application is searched. If the keys are similar enough it is returned as a
+//! suggestion.
+//!
+//! Only sources implementing the [`keys`](crate::source::Source::keys) method
+//! can be checked for unused keys.
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::ConfPath;
+//! # use justconfig::item::ValueExtractor;
+//! # use justconfig::sources::text::ConfigText;
+//! #
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new("max_conection=10".as_bytes(), "myconfig.conf").unwrap());
+//!
+//! let max_connection: u32 = conf.get(conf.root().push("max_connection")).try_value().unwrap().unwrap_or(5);
+//!
+//! let error = conf.check_unused().unwrap_err();
+//! assert_eq!(error.to_string(), "Unknown config keys:\n  'max_conection'@['conf:myconfig.conf:1'] (did you mean 'max_connection'?)");
+//! ```
+use crate::confpath::ConfPath;
+use crate::item::SourceLocation;
+use std::sync::Arc;
+use std::fmt;
+
+/// Configuration key that was never requested by the application.
+#[derive(Debug)]
+pub struct UnusedKey {
+	key: ConfPath,
+	locations: Vec<Arc<dyn SourceLocation>>,
+	suggestion: Option<ConfPath>
+}
+
+impl UnusedKey {
+	pub(crate) fn new(key: ConfPath, locations: Vec<Arc<dyn SourceLocation>>, used_keys: &[ConfPath]) -> Self {
+		let suggestion = suggest(&key, used_keys);
+
+		Self {
+			key,
+			locations,
+			suggestion
+		}
+	}
+
+	/// Returns the configuration path of the unused key.
+	pub fn key(&self) -> &ConfPath {
+		&self.key
+	}
+
+	/// Returns the source locations of all values set for the unused key.
+	pub fn locations(&self) -> &[Arc<dyn SourceLocation>] {
+		&self.locations
+	}
+
+	/// Returns the requested key that is the most similar to the unused key.
+	///
+	/// If no requested key is similar enough `None` is returned.
+	pub fn suggestion(&self) -> Option<&ConfPath> {
+		self.suggestion.as_ref()
+	}
+}
+
+impl fmt::Display for UnusedKey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "'{}'@[", self.key)?;
+		for (i, location) in self.locations.iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+
+			write!(f, "'{}'", location)?;
+		}
+		write!(f, "]")?;
+
+		if let Some(suggestion) = &self.suggestion {
+			write!(f, " (did you mean '{}'?)", suggestion)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Calculates the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+
+	for (i, ca) in a.chars().enumerate() {
+		let mut diagonal = row[0];
+		row[0] = i + 1;
+
+		for (j, cb) in b.iter().enumerate() {
+			let substitution = diagonal + if ca == *cb { 0 } else { 1 };
+			diagonal = row[j + 1];
+			row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+		}
+	}
+
+	row[b.len()]
+}
+
+/// Returns the used key with the smallest edit distance to `key`.
+///
+/// To not suggest completely unrelated keys the distance must not exceed a
+/// third of the length of the unused key.
+fn suggest(key: &ConfPath, used_keys: &[ConfPath]) -> Option<ConfPath> {
+	let key_name = key.to_string();
+	let max_distance = std::cmp::max(1, key_name.chars().count() / 3);
+
+	used_keys.iter()
+		.map(|used_key| (edit_distance(&key_name, &used_key.to_string()), used_key))
+		.filter(|(distance, _)| *distance <= max_distance)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, used_key)| used_key.clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Config;
+	use crate::error::ConfigError;
+	use crate::item::ValueExtractor;
+	use crate::sources::text::ConfigText;
+
+	fn prepare_test_config() -> Config {
+		let config_file = r#"
+max_conection=10
+timeout=5
+[db]
+host=localhost
+prot=5432
+"#;
+
+		let mut c = Config::default();
+		c.add_source(ConfigText::new(config_file.as_bytes(), "myfile").unwrap());
+
+		c
+	}
+
+	#[test]
+	fn distance() {
+		assert_eq!(edit_distance("", ""), 0);
+		assert_eq!(edit_distance("abc", ""), 3);
+		assert_eq!(edit_distance("", "abc"), 3);
+		assert_eq!(edit_distance("abc", "abc"), 0);
+		assert_eq!(edit_distance("max_conection", "max_connection"), 1);
+		assert_eq!(edit_distance("prot", "port"), 2);
+		assert_eq!(edit_distance("kitten", "sitting"), 3);
+	}
+
+	#[test]
+	fn unused_keys() {
+		let c = prepare_test_config();
+
+		let _: Option<u32> = c.get(c.root().push("max_connection")).try_value().unwrap();
+		let _: u32 = c.get(c.root().push("timeout")).value().unwrap();
+		let _: String = c.get(c.root().push_all(["db", "host"])).value().unwrap();
+		let _: Option<u32> = c.get(c.root().push_all(["db", "port"])).try_value().unwrap();
+
+		let unused = c.unused_keys();
+		assert_eq!(unused.len(), 2);
+
+		assert_eq!(*unused[0].key(), ConfPath::from(&["db", "prot"]));
+		assert_eq!(*unused[0].suggestion().unwrap(), ConfPath::from(&["db", "port"]));
+		assert_eq!(unused[0].locations().iter().map(|l| l.to_string()).collect::<Vec<_>>(), ["conf:myfile:6"]);
+
+		assert_eq!(*unused[1].key(), ConfPath::from(&["max_conection"]));
+		assert_eq!(*unused[1].suggestion().unwrap(), ConfPath::from(&["max_connection"]));
+		assert_eq!(unused[1].locations().iter().map(|l| l.to_string()).collect::<Vec<_>>(), ["conf:myfile:2"]);
+	}
+
+	#[test]
+	fn no_suggestion() {
+		let c = prepare_test_config();
+
+		let _: Option<u32> = c.get(c.root().push("something_else")).try_value().unwrap();
+
+		assert!(c.unused_keys().iter().all(|u| u.suggestion().is_none()));
+	}
+
+	#[test]
+	fn check_unused() {
+		let c = prepare_test_config();
+
+		let _: Option<u32> = c.get(c.root().push("max_connection")).try_value().unwrap();
+		let _: Option<u32> = c.get(c.root().push_all(["db", "port"])).try_value().unwrap();
+
+		match c.check_unused() {
+			Err(ConfigError::UnusedKeys(unused)) => assert_eq!(unused.len(), 4),
+			_ => panic!("Unused keys not detected.")
+		}
+
+		let _: u32 = c.get(c.root().push("timeout")).value().unwrap();
+		let _: String = c.get(c.root().push_all(["db", "host"])).value().unwrap();
+		let _: Option<u32> = c.get(c.root().push("max_conection")).try_value().unwrap();
+		let _: Option<u32> = c.get(c.root().push_all(["db", "prot"])).try_value().unwrap();
+
+		assert!(c.check_unused().is_ok());
+	}
+}
@@ -81,7 +81,7 @@
 //!
 //! // This is the necessary wrapper to ensure the conversion of StringItem to
 //! // to TypedItem<T>.
-//! impl <T: FromStr> IsFrobable<T> for Result<StringItem, ConfigError> where T::Err: Error + 'static {
+//! impl <T: FromStr> IsFrobable<T> for Result<StringItem, ConfigError> where T::Err: Error + Send + Sync + 'static {
 //!   fn isFrobable(self, max_frobability: u8) -> Result<TypedItem<T>, ConfigError> {
 //!     (self.try_into() as Result<TypedItem<T>, ConfigError>).isFrobable(max_frobability)
 //!   }
@@ -264,7 +264,7 @@ impl <T: FromStr + PartialOrd + fmt::Display> Range<T> for Result<TypedItem<T>,
 	}
 }
 
-impl <T: FromStr + PartialOrd + fmt::Display> Range<T> for Result<StringItem, ConfigError> where T::Err: Error + 'static {
+impl <T: FromStr + PartialOrd + fmt::Display> Range<T> for Result<StringItem, ConfigError> where T::Err: Error + Send + Sync + 'static {
 	fn min(self, minimum: T) -> Result<TypedItem<T>, ConfigError> {
 		(self.try_into() as Result<TypedItem<T>, ConfigError>).min(minimum)
 	}
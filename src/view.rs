@@ -0,0 +1,182 @@
+//! Scoped views of the configuration.
+//!
+//! Components of an application often only need their own part of the
+//! configuration. Calling [`scope`](crate::Config::scope) returns a
+//! [`ConfigView`] that only sees the configuration items below a prefix.
+//! All configuration paths passed to the view are relative to this prefix. The
+//! component does not need to know where its configuration is located within
+//! the configuration tree.
+//!
+//! A view only holds a reference to the [`Config`] and the prefix. It is cheap
+//! to clone and can be scoped further by calling [`scope`](ConfigView::scope).
+//!
+//! Configuration items returned by a view contain the absolute configuration
+//! path. Therefore error messages show the full path of the faulty
+//! configuration item.
+//!
+//! ```rust
+//! # use justconfig::Config;
+//! # use justconfig::ConfPath;
+//! # use justconfig::item::ValueExtractor;
+//! # use justconfig::sources::text::ConfigText;
+//! #
+//! let mut conf = Config::default();
+//! conf.add_source(ConfigText::new("server.http.port=8080\nserver.http.host=localhost\n".as_bytes(), "myconfig.conf").unwrap());
+//!
+//! let http = conf.scope(conf.root().push_all(["server", "http"]));
+//!
+//! let port: u16 = http.get(http.root().push("port")).value().unwrap();
+//! assert_eq!(port, 8080);
+//! assert_eq!(http.keys(), [ConfPath::from(&["host"]), ConfPath::from(&["port"])]);
+//!
+//! let timeout: Result<u32, _> = http.get(http.root().push("timeout")).value();
+//! assert_eq!(timeout.unwrap_err().to_string(), "Missing value for config key 'server.http.timeout'.");
+//! ```
+use crate::Config;
+use crate::confpath::ConfPath;
+use crate::error::ConfigError;
+use crate::explain::Explanation;
+use crate::item::StringItem;
+
+/// View of the configuration items below a prefix.
+///
+/// See the [`view`](mod@crate::view) module for details.
+#[derive(Clone)]
+pub struct ConfigView<'a> {
+	config: &'a Config,
+	prefix: ConfPath,
+	path_root: ConfPath
+}
+
+impl <'a> ConfigView<'a> {
+	pub(crate) fn new(config: &'a Config, prefix: ConfPath) -> Self {
+		Self {
+			config,
+			prefix,
+			path_root: ConfPath::default()
+		}
+	}
+
+	/// Returns the configuration this view belongs to.
+	pub fn config(&self) -> &'a Config {
+		self.config
+	}
+
+	/// Returns the absolute configuration path of this view.
+	pub fn prefix(&self) -> &ConfPath {
+		&self.prefix
+	}
+
+	/// Convenience method to get a ConfPath instance.
+	///
+	/// Works like [`Config::root`]. The configuration paths built from the
+	/// returned `ConfPath` are relative to the prefix of this view.
+	pub fn root(&self) -> ConfPath {
+		self.path_root.clone()
+	}
+
+	/// Get the configuration value identified by the passed relative
+	/// `ConfPath`.
+	///
+	/// Works like [`Config::get`]. The returned configuration item carries the
+	/// absolute configuration path.
+	pub fn get(&self, key: ConfPath) -> Result<StringItem, ConfigError> {
+		self.config.get(self.absolute(&key))
+	}
+
+	/// Explain where the value of a configuration item is coming from.
+	///
+	/// Works like [`Config::explain`] with a path relative to the prefix of
+	/// this view.
+	pub fn explain(&self, key: ConfPath) -> Explanation {
+		self.config.explain(self.absolute(&key))
+	}
+
+	/// Returns a view of the configuration items below the passed relative
+	/// path.
+	pub fn scope(&self, path: ConfPath) -> ConfigView<'a> {
+		Self::new(self.config, self.absolute(&path))
+	}
+
+	/// Enumerate all configuration keys below the prefix of this view.
+	///
+	/// Works like [`Config::keys`]. The returned keys are relative to the
+	/// prefix of this view. If the prefix itself is a configuration key, it is
+	/// not returned.
+	pub fn keys(&self) -> Vec<ConfPath> {
+		self.config.keys_under(&self.prefix).into_iter()
+			.filter_map(|key| key.strip_prefix(&self.prefix))
+			.filter(|key| !key.is_root())
+			.map(|key| self.path_root.join(&key))
+			.collect()
+	}
+
+	/// Enumerate all configuration keys below the passed relative prefix.
+	///
+	/// Works like [`Config::keys_under`]. The returned keys are relative to the
+	/// prefix of this view.
+	pub fn keys_under(&self, prefix: &ConfPath) -> Vec<ConfPath> {
+		self.keys().into_iter().filter(|key| key.starts_with(prefix)).collect()
+	}
+
+	fn absolute(&self, key: &ConfPath) -> ConfPath {
+		self.prefix.join(key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::item::ValueExtractor;
+	use crate::sources::text::ConfigText;
+
+	fn prepare_test_config() -> Config {
+		let mut c = Config::default();
+		c.add_source(ConfigText::new("a=root\na.b.c=1\na.b.d=x\na.e=2\nf=3\n".as_bytes(), "myconfig.conf").unwrap());
+
+		c
+	}
+
+	#[test]
+	fn get() {
+		let c = prepare_test_config();
+		let view = c.scope(ConfPath::from(&["a"]));
+
+		assert_eq!((view.get(ConfPath::from(&["e"])).value() as Result<u32, ConfigError>).unwrap(), 2);
+		assert_eq!((view.get(view.root().push_all(["b", "c"])).value() as Result<u32, ConfigError>).unwrap(), 1);
+		assert_eq!(view.get(ConfPath::from(&["e"])).unwrap().key(), &ConfPath::from(&["a", "e"]));
+		assert!(view.get(ConfPath::from(&["f"])).is_err());
+		assert_eq!(view.explain(ConfPath::from(&["e"])).key(), &ConfPath::from(&["a", "e"]));
+	}
+
+	#[test]
+	fn nested() {
+		let c = prepare_test_config();
+		let view = c.scope(ConfPath::from(&["a"])).scope(ConfPath::from(&["b"]));
+
+		assert_eq!(view.prefix(), &ConfPath::from(&["a", "b"]));
+		assert_eq!(view.keys(), [ConfPath::from(&["c"]), ConfPath::from(&["d"])]);
+
+		let error = (view.clone().get(ConfPath::from(&["d"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "invalid digit found in string@'conf:myconfig.conf:3'");
+
+		let error = (view.get(ConfPath::from(&["missing"])).value() as Result<u32, ConfigError>).unwrap_err();
+		assert_eq!(error.to_string(), "Missing value for config key 'a.b.missing'.");
+	}
+
+	#[test]
+	fn keys() {
+		let c = prepare_test_config();
+		let view = c.scope(ConfPath::from(&["a"]));
+
+		assert_eq!(view.keys(), [ConfPath::from(&["b", "c"]), ConfPath::from(&["b", "d"]), ConfPath::from(&["e"])]);
+		assert_eq!(view.keys_under(&ConfPath::from(&["b"])), [ConfPath::from(&["b", "c"]), ConfPath::from(&["b", "d"])]);
+		assert!(c.scope(ConfPath::from(&["missing"])).keys().is_empty());
+
+		// Requests through the view mark the absolute keys as used.
+		for key in view.keys() {
+			let _ = view.get(key);
+		}
+		assert_eq!(c.unused_keys().iter().map(|unused| unused.key().clone()).collect::<Vec<_>>(), [ConfPath::from(&["a"]), ConfPath::from(&["f"])]);
+	}
+}